@@ -0,0 +1,317 @@
+//! Lay out every sub-texture of a Kenney spritesheet in
+//! a grid, labeled with its atlas index and name.
+//!
+//! - Tab: switch between the bundled sheets
+//! - type: search sub-textures by name
+//! - Backspace/Escape: edit/clear the search
+//! - mouse wheel, PageUp/PageDown: zoom
+//! - arrow keys: pan
+
+use bevy::{
+    input::mouse::MouseWheel, prelude::*, sprite::Anchor,
+};
+use space_shooter::kenney_assets::*;
+
+const SHEETS: [&str; 3] = [
+    "space_sheet.xml",
+    "spaceShooter2_spritesheet_2X.xml",
+    "uipackSpace_sheet.xml",
+];
+const CELL_SIZE: f32 = 160.;
+const LABEL_HEIGHT: f32 = 40.;
+const COLUMNS: usize = 12;
+const PAN_SPEED: f32 = 800.;
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins
+                .set(ImagePlugin::default_nearest()),
+            KenneyAssetPlugin,
+        ))
+        .init_resource::<Inspector>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                switch_sheet,
+                search,
+                layout_grid,
+                update_header,
+                zoom,
+                pan,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+#[derive(Resource, Default)]
+struct Inspector {
+    sheets: Vec<Handle<KenneySpriteSheetAsset>>,
+    current: usize,
+    query: String,
+    /// number of sub-textures matching `query`
+    matches: usize,
+    /// the grid needs to be rebuilt
+    dirty: bool,
+}
+
+#[derive(Component)]
+struct GridCell;
+
+#[derive(Component)]
+struct Header;
+
+fn setup(
+    mut commands: Commands,
+    mut inspector: ResMut<Inspector>,
+    asset_server: Res<AssetServer>,
+) {
+    inspector.sheets = SHEETS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+    inspector.dirty = true;
+
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_background_color(Color::rgba(0., 0., 0., 0.8))
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.),
+            left: Val::Px(0.),
+            padding: UiRect::all(Val::Px(10.)),
+            ..default()
+        }),
+        Header,
+    ));
+}
+
+fn switch_sheet(
+    input: Res<ButtonInput<KeyCode>>,
+    mut inspector: ResMut<Inspector>,
+    mut camera: Query<
+        (&mut Transform, &mut OrthographicProjection),
+        With<Camera>,
+    >,
+) {
+    if !input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+    inspector.current =
+        (inspector.current + 1) % inspector.sheets.len();
+    inspector.dirty = true;
+    for (mut transform, mut projection) in &mut camera {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+        projection.scale = 1.;
+    }
+}
+
+fn search(
+    input: Res<ButtonInput<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut inspector: ResMut<Inspector>,
+) {
+    for event in characters.read() {
+        for character in event.char.chars() {
+            if !character.is_control() {
+                inspector.query.push(character);
+                inspector.dirty = true;
+            }
+        }
+    }
+    if input.just_pressed(KeyCode::Backspace) {
+        inspector.query.pop();
+        inspector.dirty = true;
+    }
+    if input.just_pressed(KeyCode::Escape) {
+        inspector.query.clear();
+        inspector.dirty = true;
+    }
+}
+
+fn layout_grid(
+    mut commands: Commands,
+    mut inspector: ResMut<Inspector>,
+    spritesheets: Res<Assets<KenneySpriteSheetAsset>>,
+    cells: Query<Entity, With<GridCell>>,
+) {
+    if !inspector.dirty {
+        return;
+    }
+    let Some(sheet) = spritesheets
+        .get(&inspector.sheets[inspector.current])
+    else {
+        // try again once the sheet has loaded
+        return;
+    };
+    inspector.dirty = false;
+
+    for entity in &cells {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let query = inspector.query.to_lowercase();
+    let matching: Vec<(usize, &SubTexture)> = sheet
+        .textures
+        .iter()
+        .enumerate()
+        .filter(|(_, texture)| {
+            texture.name.to_lowercase().contains(&query)
+        })
+        .collect();
+    inspector.matches = matching.len();
+
+    let grid_width = CELL_SIZE * COLUMNS as f32;
+    for (position, (index, texture)) in
+        matching.into_iter().enumerate()
+    {
+        let column = position % COLUMNS;
+        let row = position / COLUMNS;
+        let cell_center = Vec3::new(
+            column as f32 * CELL_SIZE - grid_width / 2.
+                + CELL_SIZE / 2.,
+            -(row as f32) * (CELL_SIZE + LABEL_HEIGHT),
+            0.,
+        );
+
+        // shrink large sprites to fit their cell, but
+        // never blow small ones up
+        let size = texture.rect().size();
+        let fit =
+            (CELL_SIZE * 0.8 / size.max_element()).min(1.);
+
+        commands
+            .spawn((
+                SpatialBundle::from_transform(
+                    Transform::from_translation(
+                        cell_center,
+                    ),
+                ),
+                GridCell,
+            ))
+            .with_children(|cell| {
+                cell.spawn((
+                    SpriteBundle {
+                        texture: sheet.sheet.clone(),
+                        sprite: Sprite {
+                            custom_size: Some(size * fit),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    TextureAtlas {
+                        index,
+                        layout: sheet
+                            .texture_atlas_layout
+                            .clone(),
+                    },
+                ));
+                cell.spawn(Text2dBundle {
+                    text: Text::from_section(
+                        format!(
+                            "{index}\n{}",
+                            texture.name
+                        ),
+                        TextStyle {
+                            font_size: 14.,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_justify(JustifyText::Center),
+                    text_anchor: Anchor::TopCenter,
+                    transform: Transform::from_xyz(
+                        0.,
+                        -CELL_SIZE / 2.,
+                        1.,
+                    ),
+                    ..default()
+                });
+            });
+    }
+}
+
+fn update_header(
+    inspector: Res<Inspector>,
+    mut header: Query<&mut Text, With<Header>>,
+) {
+    if !inspector.is_changed() {
+        return;
+    }
+    for mut text in &mut header {
+        text.sections[0].value = format!(
+            "{} ({} matches)\nsearch: {}_\nTab: next sheet | type to search | Esc: clear | wheel/PgUp/PgDn: zoom | arrows: pan",
+            SHEETS[inspector.current],
+            inspector.matches,
+            inspector.query,
+        );
+    }
+}
+
+fn zoom(
+    input: Res<ButtonInput<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut projection: Query<
+        &mut OrthographicProjection,
+        With<Camera>,
+    >,
+) {
+    let mut zoom = 0.;
+    for event in wheel.read() {
+        zoom -= event.y.signum() * 0.1;
+    }
+    if input.just_pressed(KeyCode::PageUp) {
+        zoom -= 0.25;
+    }
+    if input.just_pressed(KeyCode::PageDown) {
+        zoom += 0.25;
+    }
+    if zoom == 0. {
+        return;
+    }
+    for mut projection in &mut projection {
+        projection.scale = (projection.scale * (1. + zoom))
+            .clamp(0.1, 10.);
+    }
+}
+
+fn pan(
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut camera: Query<
+        (&mut Transform, &OrthographicProjection),
+        With<Camera>,
+    >,
+) {
+    let mut direction = Vec2::ZERO;
+    if input.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.;
+    }
+    if input.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.;
+    }
+    if input.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.;
+    }
+    if input.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.;
+    }
+    for (mut transform, projection) in &mut camera {
+        let delta = direction
+            * PAN_SPEED
+            * projection.scale
+            * time.delta_seconds();
+        transform.translation += delta.extend(0.);
+    }
+}
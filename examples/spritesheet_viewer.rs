@@ -71,6 +71,7 @@ fn input(
     let mut atlas = atlas.single_mut();
 
     if input.just_pressed(KeyCode::Space) {
-        atlas.index += 1;
+        atlas.index =
+            (atlas.index + 1) % kenney_sheet.textures.len();
     }
 }
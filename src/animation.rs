@@ -0,0 +1,480 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    kenney_assets::{
+        KenneySpriteSheetAsset, KenneySpriteSheetReloaded,
+        TagDirection,
+    },
+    ui::pause::Pausable,
+};
+
+pub struct SpriteAnimationPlugin;
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AnimationFinished>()
+            .add_systems(
+                PreUpdate,
+                remap_animation_clips
+                    .run_if(on_event::<
+                        KenneySpriteSheetReloaded,
+                    >()),
+            )
+            .add_systems(
+                Update,
+                animate_sprites.run_if(resource_equals(
+                    Pausable::NotPaused,
+                )),
+            );
+    }
+}
+
+/// An ordered list of atlas indices that make up an
+/// animation.
+///
+/// Kenney sheets name frame series with a shared prefix
+/// and a numeric suffix (`fire00.png`, `fire01.png`,
+/// ...), so clips are built by grouping sub-textures on
+/// that convention.
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub name: String,
+    pub frames: Vec<usize>,
+}
+
+impl AnimationClip {
+    /// Build the clip for every sub-texture named
+    /// `{prefix}{number}`, ordered by number.
+    ///
+    /// ```ignore
+    /// let fire = AnimationClip::from_prefix(space_sheet, "fire");
+    /// ```
+    pub fn from_prefix(
+        sheet: &KenneySpriteSheetAsset,
+        prefix: &str,
+    ) -> Option<AnimationClip> {
+        let mut frames: Vec<(u32, usize)> = sheet
+            .textures
+            .iter()
+            .enumerate()
+            .filter_map(|(index, texture)| {
+                let (frame_prefix, number) =
+                    split_frame_name(&texture.name)?;
+                (frame_prefix == prefix)
+                    .then_some((number, index))
+            })
+            .collect();
+        if frames.is_empty() {
+            return None;
+        }
+        frames.sort();
+        Some(AnimationClip {
+            name: prefix.to_string(),
+            frames: frames
+                .into_iter()
+                .map(|(_, index)| index)
+                .collect(),
+        })
+    }
+
+    /// Build a clip from sub-textures listed by name, for
+    /// when a numbered series mixes frames that don't
+    /// belong together. `None` if any name is missing.
+    ///
+    /// ```ignore
+    /// let fire = AnimationClip::from_names(
+    ///     space_sheet,
+    ///     "fire",
+    ///     &["fire01.png", "fire02.png"],
+    /// );
+    /// ```
+    pub fn from_names(
+        sheet: &KenneySpriteSheetAsset,
+        name: &str,
+        frame_names: &[&str],
+    ) -> Option<AnimationClip> {
+        let frames = frame_names
+            .iter()
+            .map(|frame_name| {
+                sheet.textures.iter().position(|texture| {
+                    texture.name == *frame_name
+                })
+            })
+            .collect::<Option<Vec<usize>>>()?;
+        if frames.is_empty() {
+            return None;
+        }
+        Some(AnimationClip {
+            name: name.to_string(),
+            frames,
+        })
+    }
+
+    /// Every numbered frame series in the sheet, keyed by
+    /// prefix.
+    pub fn all_from_sheet(
+        sheet: &KenneySpriteSheetAsset,
+    ) -> HashMap<String, AnimationClip> {
+        let mut series: HashMap<String, Vec<(u32, usize)>> =
+            HashMap::default();
+        for (index, texture) in
+            sheet.textures.iter().enumerate()
+        {
+            if let Some((prefix, number)) =
+                split_frame_name(&texture.name)
+            {
+                series
+                    .entry(prefix.to_string())
+                    .or_default()
+                    .push((number, index));
+            }
+        }
+        series
+            .into_iter()
+            .map(|(name, mut frames)| {
+                frames.sort();
+                let clip = AnimationClip {
+                    name: name.clone(),
+                    frames: frames
+                        .into_iter()
+                        .map(|(_, index)| index)
+                        .collect(),
+                };
+                (name, clip)
+            })
+            .collect()
+    }
+
+    /// Build a clip from an Aseprite frame tag, along with
+    /// the mode the tag asks for.
+    pub fn from_tag(
+        sheet: &KenneySpriteSheetAsset,
+        tag_name: &str,
+    ) -> Option<(AnimationClip, AnimationMode)> {
+        let tag = sheet
+            .tags
+            .iter()
+            .find(|tag| tag.name == tag_name)?;
+        let mut frames: Vec<usize> = (tag.from..=tag.to)
+            .filter(|index| *index < sheet.textures.len())
+            .collect();
+        let mode = match tag.direction {
+            TagDirection::Forward => AnimationMode::Loop,
+            TagDirection::Reverse => {
+                frames.reverse();
+                AnimationMode::Loop
+            }
+            TagDirection::PingPong => {
+                AnimationMode::PingPong
+            }
+            TagDirection::PingPongReverse => {
+                frames.reverse();
+                AnimationMode::PingPong
+            }
+        };
+        Some((
+            AnimationClip {
+                name: tag.name.clone(),
+                frames,
+            },
+            mode,
+        ))
+    }
+}
+
+/// Splits `laserBlue01.png` into `("laserBlue", 1)`.
+///
+/// A separating `_` is dropped, so `wingRed_3.png`
+/// becomes `("wingRed", 3)`.
+fn split_frame_name(name: &str) -> Option<(&str, u32)> {
+    let stem = name
+        .rsplit_once('.')
+        .map_or(name, |(stem, _extension)| stem);
+    let prefix =
+        stem.trim_end_matches(|c: char| c.is_ascii_digit());
+    if prefix.len() == stem.len() || prefix.is_empty() {
+        return None;
+    }
+    let number = stem[prefix.len()..].parse().ok()?;
+    Some((prefix.trim_end_matches('_'), number))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// start over after the last frame
+    Loop,
+    /// stop on the last frame and send
+    /// [`AnimationFinished`]
+    Once,
+    /// play forwards, then backwards, forever
+    PingPong,
+}
+
+/// Sent when an [`AnimationMode::Once`] animation shows
+/// its last frame.
+#[derive(Debug, Event)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub clip: String,
+}
+
+/// Drives the [`TextureAtlas`] index of the same entity
+/// through an [`AnimationClip`].
+#[derive(Component, Debug)]
+pub struct SpriteAnimation {
+    pub clip: AnimationClip,
+    pub mode: AnimationMode,
+    timer: Timer,
+    frame: usize,
+    reversing: bool,
+    finished: bool,
+}
+
+impl SpriteAnimation {
+    /// A `frames_per_second` of zero or less holds the
+    /// first frame rather than panicking.
+    pub fn new(
+        clip: AnimationClip,
+        frames_per_second: f32,
+        mode: AnimationMode,
+    ) -> Self {
+        // NaN is clamped too
+        let frames_per_second =
+            frames_per_second.max(f32::EPSILON);
+        Self {
+            clip,
+            mode,
+            timer: Timer::from_seconds(
+                1. / frames_per_second,
+                TimerMode::Repeating,
+            ),
+            frame: 0,
+            reversing: false,
+            finished: false,
+        }
+    }
+    /// The atlas index that should currently be shown
+    pub fn current_index(&self) -> Option<usize> {
+        self.clip.frames.get(self.frame).copied()
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    pub fn restart(&mut self) {
+        self.timer.reset();
+        self.frame = 0;
+        self.reversing = false;
+        self.finished = false;
+    }
+    /// Advance one frame. Returns `true` if this step
+    /// finished a [`AnimationMode::Once`] animation.
+    fn step(&mut self) -> bool {
+        let last = self.clip.frames.len().saturating_sub(1);
+        match self.mode {
+            AnimationMode::Loop => {
+                self.frame = if self.frame >= last {
+                    0
+                } else {
+                    self.frame + 1
+                };
+            }
+            AnimationMode::Once => {
+                if self.frame < last {
+                    self.frame += 1;
+                }
+                if self.frame == last && !self.finished {
+                    self.finished = true;
+                    return true;
+                }
+            }
+            AnimationMode::PingPong => {
+                if self.reversing {
+                    if self.frame == 0 {
+                        self.reversing = false;
+                        self.frame = last.min(1);
+                    } else {
+                        self.frame -= 1;
+                    }
+                } else if self.frame >= last {
+                    self.reversing = true;
+                    self.frame = last.saturating_sub(1);
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+        false
+    }
+}
+
+fn animate_sprites(
+    time: Res<Time>,
+    mut animations: Query<(
+        Entity,
+        &mut SpriteAnimation,
+        &mut TextureAtlas,
+    )>,
+    mut finished: EventWriter<AnimationFinished>,
+) {
+    for (entity, mut animation, mut atlas) in
+        &mut animations
+    {
+        if animation.finished
+            || animation.clip.frames.is_empty()
+        {
+            continue;
+        }
+        animation.timer.tick(time.delta());
+        for _ in
+            0..animation.timer.times_finished_this_tick()
+        {
+            if animation.step() {
+                finished.send(AnimationFinished {
+                    entity,
+                    clip: animation.clip.name.clone(),
+                });
+                break;
+            }
+        }
+        if let Some(index) = animation.current_index() {
+            if atlas.index != index {
+                atlas.index = index;
+            }
+        }
+    }
+}
+
+/// Clip frames are atlas indices, so they need to follow
+/// their sub-textures when a sheet is hot reloaded.
+fn remap_animation_clips(
+    mut events: EventReader<KenneySpriteSheetReloaded>,
+    sheets: Res<Assets<KenneySpriteSheetAsset>>,
+    mut animations: Query<(
+        &mut SpriteAnimation,
+        &TextureAtlas,
+    )>,
+) {
+    for event in events.read() {
+        let Some(sheet) = sheets.get(event.sheet) else {
+            continue;
+        };
+        for (mut animation, atlas) in &mut animations {
+            if atlas.layout != sheet.texture_atlas_layout {
+                continue;
+            }
+            let frames: Vec<usize> = animation
+                .clip
+                .frames
+                .iter()
+                .filter_map(|index| event.new_index(*index))
+                .collect();
+            animation.clip.frames = frames;
+            let last = animation
+                .clip
+                .frames
+                .len()
+                .saturating_sub(1);
+            animation.frame = animation.frame.min(last);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn animation(
+        frames: usize,
+        mode: AnimationMode,
+    ) -> SpriteAnimation {
+        SpriteAnimation::new(
+            AnimationClip {
+                name: "test".to_string(),
+                frames: (0..frames).collect(),
+            },
+            10.,
+            mode,
+        )
+    }
+
+    fn shown_frames(
+        animation: &mut SpriteAnimation,
+        steps: usize,
+    ) -> Vec<usize> {
+        (0..steps)
+            .map(|_| {
+                animation.step();
+                animation.frame
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_frame_name_orders_numerically() {
+        let mut frames: Vec<(&str, u32)> =
+            ["fire10.png", "fire2.png", "fire01.png"]
+                .into_iter()
+                .filter_map(split_frame_name)
+                .collect();
+        frames.sort();
+        assert_eq!(
+            frames,
+            [("fire", 1), ("fire", 2), ("fire", 10)]
+        );
+        assert_eq!(
+            split_frame_name("wingRed_3.png"),
+            Some(("wingRed", 3))
+        );
+    }
+
+    #[test]
+    fn split_frame_name_skips_non_numeric_suffixes() {
+        for name in [
+            "fire.png",
+            "laserBlue01a.png",
+            "ufoRed.png",
+            "42.png",
+        ] {
+            assert_eq!(
+                split_frame_name(name),
+                None,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn loop_wraps_to_the_first_frame() {
+        let mut animation =
+            animation(3, AnimationMode::Loop);
+        assert_eq!(
+            shown_frames(&mut animation, 5),
+            [1, 2, 0, 1, 2]
+        );
+        assert!(!animation.is_finished());
+    }
+
+    #[test]
+    fn ping_pong_reverses_at_either_end() {
+        let mut animation =
+            animation(3, AnimationMode::PingPong);
+        assert_eq!(
+            shown_frames(&mut animation, 6),
+            [1, 2, 1, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn once_finishes_exactly_once() {
+        let mut animation =
+            animation(3, AnimationMode::Once);
+        let finished: Vec<bool> =
+            (0..5).map(|_| animation.step()).collect();
+        assert_eq!(
+            finished,
+            [false, true, false, false, false]
+        );
+        assert_eq!(animation.frame, 2);
+        assert!(animation.is_finished());
+    }
+}
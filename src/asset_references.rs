@@ -0,0 +1,378 @@
+//! Every atlas index and sub-texture name the game
+//! hardcodes, grouped by the sheet they point into.
+//!
+//! `cargo run --bin validate_assets` checks these
+//! against the sheets in `assets/`.
+
+use bevy::sprite::BorderRect;
+use std::fmt;
+
+use crate::{
+    kenney_assets::SubTexture,
+    meteors::{
+        METEOR_BIG_ATLAS_INDEX, METEOR_MEDIUM_ATLAS_INDEX,
+        METEOR_SMALL_ATLAS_INDEX,
+    },
+    ship::{
+        PlayerShipType, ShipColor, ENGINE_FIRE_ATLAS_INDEX,
+        ENGINE_FIRE_FRAMES,
+    },
+    ufo::{
+        UFO_ATLAS_INDEX, UFO_COLLIDER_TEXTURE,
+        UFO_LASER_ATLAS_INDEX,
+    },
+    ui::{
+        button::BUTTON_TEXTURE,
+        widgets::{
+            SELECTED_WIDGET_TEXTURE, SLIDER_TRACK_TEXTURE,
+            WIDGET_TEXTURE,
+        },
+        PANEL_TEXTURE,
+    },
+};
+
+/// A single use of a spritesheet in the game
+#[derive(Debug, Clone)]
+pub enum AssetReference {
+    /// An atlas index, and the name of the sub-texture
+    /// it is expected to point at
+    Index {
+        index: usize,
+        expected_name: &'static str,
+        used_by: &'static str,
+    },
+    /// A sub-texture looked up by name
+    Name {
+        name: &'static str,
+        used_by: &'static str,
+    },
+    /// A sub-texture drawn nine-sliced, which needs slice
+    /// borders from the xml or the `.slices.json` sidecar
+    NineSlice {
+        name: &'static str,
+        used_by: &'static str,
+    },
+}
+
+/// A sheet path, relative to `assets/`, and everything
+/// that references it
+pub struct SheetReferences {
+    pub path: &'static str,
+    pub references: Vec<AssetReference>,
+}
+
+pub fn all_sheet_references() -> Vec<SheetReferences> {
+    vec![
+        SheetReferences {
+            path: "space_sheet.xml",
+            references: space_sheet_references(),
+        },
+        SheetReferences {
+            path: "uipackSpace_sheet.xml",
+            references: vec![
+                AssetReference::NineSlice {
+                    name: PANEL_TEXTURE,
+                    used_by: "ui::PANEL_TEXTURE",
+                },
+                AssetReference::NineSlice {
+                    name: WIDGET_TEXTURE,
+                    used_by: "ui::widgets::WIDGET_TEXTURE",
+                },
+                AssetReference::NineSlice {
+                    name: SELECTED_WIDGET_TEXTURE,
+                    used_by:
+                        "ui::widgets::SELECTED_WIDGET_TEXTURE",
+                },
+                AssetReference::NineSlice {
+                    name: SLIDER_TRACK_TEXTURE,
+                    used_by: "ui::widgets::SpawnSlider",
+                },
+            ],
+        },
+    ]
+}
+
+fn space_sheet_references() -> Vec<AssetReference> {
+    let mut references = vec![
+        AssetReference::Index {
+            index: ENGINE_FIRE_ATLAS_INDEX,
+            expected_name: "fire01.png",
+            used_by: "ship::PlayerEngineFire",
+        },
+        AssetReference::Index {
+            index: METEOR_BIG_ATLAS_INDEX,
+            expected_name: "meteorGrey_big1.png",
+            used_by: "meteors::MeteorBundle::big",
+        },
+        AssetReference::Index {
+            index: METEOR_MEDIUM_ATLAS_INDEX,
+            expected_name: "meteorGrey_med1.png",
+            used_by: "meteors::MeteorBundle::medium",
+        },
+        AssetReference::Index {
+            index: METEOR_SMALL_ATLAS_INDEX,
+            expected_name: "meteorGrey_small1.png",
+            used_by: "meteors::MeteorBundle::small",
+        },
+        AssetReference::Index {
+            index: UFO_ATLAS_INDEX,
+            expected_name: "ufoRed.png",
+            used_by: "ufo::periodically_spawn_ufo",
+        },
+        AssetReference::Name {
+            name: UFO_COLLIDER_TEXTURE,
+            used_by: "ufo::periodically_spawn_ufo",
+        },
+        AssetReference::Index {
+            index: UFO_LASER_ATLAS_INDEX,
+            expected_name: "laserRed01.png",
+            used_by: "ufo::ufo_weapon_system",
+        },
+        AssetReference::NineSlice {
+            name: BUTTON_TEXTURE,
+            used_by: "ui::button::SpawnButton",
+        },
+    ];
+
+    for name in ENGINE_FIRE_FRAMES {
+        references.push(AssetReference::Name {
+            name,
+            used_by: "ship::PlayerEngineFire::animation",
+        });
+    }
+    for color in ShipColor::ALL {
+        references.push(AssetReference::Name {
+            name: color.laser_texture(),
+            used_by: "ship::ShipColor::laser_atlas_index",
+        });
+        for ship_type in PlayerShipType::all_ships() {
+            references.push(AssetReference::Name {
+                name: ship_type.texture(color),
+                used_by:
+                    "ship::PlayerShipType::atlas_index",
+            });
+            references.push(AssetReference::Name {
+                name: ship_type.life_texture(color),
+                used_by: "ship::PlayerShipType::life_atlas_index",
+            });
+        }
+    }
+
+    references
+}
+
+/// Something wrong with a sheet or a reference into it
+#[derive(Debug)]
+pub enum ValidationError {
+    IndexOutOfRange {
+        index: usize,
+        len: usize,
+        used_by: &'static str,
+    },
+    IndexNameMismatch {
+        index: usize,
+        expected_name: &'static str,
+        actual_name: String,
+        used_by: &'static str,
+    },
+    MissingName {
+        name: &'static str,
+        used_by: &'static str,
+    },
+    OutOfImageBounds {
+        name: String,
+        right: u32,
+        bottom: u32,
+        image_width: u32,
+        image_height: u32,
+    },
+    /// The `.slices.json` sidecar has borders for a name
+    /// that isn't in the sheet
+    UnknownSlice { name: String },
+    /// Slice borders that overlap, leaving no room for
+    /// the middle of the nine-slice
+    SliceTooLarge {
+        name: String,
+        border: BorderRect,
+        width: f32,
+        height: f32,
+    },
+    MissingNineSlice {
+        name: &'static str,
+        used_by: &'static str,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            ValidationError::IndexOutOfRange {
+                index,
+                len,
+                used_by,
+            } => write!(
+                f,
+                "{used_by}: index {index} is out of range, the sheet has {len} sub-textures"
+            ),
+            ValidationError::IndexNameMismatch {
+                index,
+                expected_name,
+                actual_name,
+                used_by,
+            } => write!(
+                f,
+                "{used_by}: index {index} is {actual_name:?}, expected {expected_name:?}"
+            ),
+            ValidationError::MissingName { name, used_by } => {
+                write!(f, "{used_by}: no sub-texture named {name:?}")
+            }
+            ValidationError::OutOfImageBounds {
+                name,
+                right,
+                bottom,
+                image_width,
+                image_height,
+            } => write!(
+                f,
+                "{name:?} extends to ({right}, {bottom}), outside the {image_width}x{image_height} image"
+            ),
+            ValidationError::UnknownSlice { name } => write!(
+                f,
+                "slices sidecar has borders for {name:?}, which isn't in the sheet"
+            ),
+            ValidationError::SliceTooLarge {
+                name,
+                border,
+                width,
+                height,
+            } => write!(
+                f,
+                "{name:?} has slice borders (left {}, right {}, top {}, bottom {}) larger than its {width}x{height} size",
+                border.left, border.right, border.top, border.bottom
+            ),
+            ValidationError::MissingNineSlice {
+                name,
+                used_by,
+            } => write!(
+                f,
+                "{used_by}: {name:?} is drawn nine-sliced but has no slice borders"
+            ),
+        }
+    }
+}
+
+/// Check `references` and the bounds of every
+/// sub-texture against a parsed sheet
+pub fn validate(
+    textures: &[SubTexture],
+    image_size: (u32, u32),
+    references: &[AssetReference],
+) -> Vec<ValidationError> {
+    let (image_width, image_height) = image_size;
+    let mut errors: Vec<ValidationError> = textures
+        .iter()
+        .filter_map(|texture| {
+            let rect = texture.rect();
+            let right = rect.max.x as u32;
+            let bottom = rect.max.y as u32;
+            (right > image_width || bottom > image_height)
+                .then(|| {
+                    ValidationError::OutOfImageBounds {
+                        name: texture.name.clone(),
+                        right,
+                        bottom,
+                        image_width,
+                        image_height,
+                    }
+                })
+        })
+        .collect();
+
+    for texture in textures {
+        let Some(border) = texture.slice else {
+            continue;
+        };
+        let size = texture.rect().size();
+        if border.left + border.right > size.x
+            || border.top + border.bottom > size.y
+        {
+            errors.push(ValidationError::SliceTooLarge {
+                name: texture.name.clone(),
+                border,
+                width: size.x,
+                height: size.y,
+            });
+        }
+    }
+
+    for reference in references {
+        match *reference {
+            AssetReference::Index {
+                index,
+                expected_name,
+                used_by,
+            } => match textures.get(index) {
+                None => {
+                    errors.push(
+                        ValidationError::IndexOutOfRange {
+                            index,
+                            len: textures.len(),
+                            used_by,
+                        },
+                    );
+                }
+                Some(texture)
+                    if texture.name != expected_name =>
+                {
+                    errors.push(
+                        ValidationError::IndexNameMismatch {
+                            index,
+                            expected_name,
+                            actual_name: texture.name.clone(),
+                            used_by,
+                        },
+                    );
+                }
+                Some(_) => {}
+            },
+            AssetReference::Name { name, used_by } => {
+                if !textures
+                    .iter()
+                    .any(|texture| texture.name == name)
+                {
+                    errors.push(ValidationError::MissingName {
+                        name,
+                        used_by,
+                    });
+                }
+            }
+            AssetReference::NineSlice { name, used_by } => {
+                match textures
+                    .iter()
+                    .find(|texture| texture.name == name)
+                {
+                    None => errors.push(
+                        ValidationError::MissingName {
+                            name,
+                            used_by,
+                        },
+                    ),
+                    Some(texture) if texture.slice.is_none() => {
+                        errors.push(
+                            ValidationError::MissingNineSlice {
+                                name,
+                                used_by,
+                            },
+                        )
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    errors
+}
@@ -49,4 +49,6 @@ pub struct ImageAssets {
     pub pattern_blueprint: Handle<Image>,
     #[asset(path = "space_sheet.xml")]
     pub space_sheet: Handle<KenneySpriteSheetAsset>,
+    #[asset(path = "uipackSpace_sheet.xml")]
+    pub ui_sheet: Handle<KenneySpriteSheetAsset>,
 }
@@ -0,0 +1,384 @@
+//! Packs a directory of PNGs into a single spritesheet
+//! plus a Kenney-format XML file that
+//! `KenneySpriteSheetAssetLoader` can load.
+//!
+//! ```sh
+//! cargo run --bin pack_spritesheet -- art/ships assets/ships --padding 2 --max-size 2048
+//! ```
+//!
+//! writes `assets/ships.png` and `assets/ships.xml`.
+
+use image::{imageops, RgbaImage};
+use std::{
+    error::Error,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const USAGE: &str = "usage: pack_spritesheet <input_dir> <output> [--padding <px>] [--max-size <px>]";
+
+struct Options {
+    input_dir: PathBuf,
+    /// where the sheet image is written
+    image_path: PathBuf,
+    /// where the Kenney xml is written, next to the image
+    xml_path: PathBuf,
+    padding: u32,
+    max_size: u32,
+}
+
+impl Options {
+    fn from_args() -> Result<Options, String> {
+        let mut args = std::env::args().skip(1);
+        let mut positional = vec![];
+        let mut padding = 2;
+        let mut max_size = 4096;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--padding" => {
+                    padding = parse_number(
+                        "--padding",
+                        args.next(),
+                    )?;
+                }
+                "--max-size" => {
+                    max_size = parse_number(
+                        "--max-size",
+                        args.next(),
+                    )?;
+                }
+                "-h" | "--help" => {
+                    return Err(USAGE.to_string())
+                }
+                _ => positional.push(PathBuf::from(arg)),
+            }
+        }
+
+        let [input_dir, output] =
+            <[PathBuf; 2]>::try_from(positional)
+                .map_err(|_| USAGE.to_string())?;
+
+        let (image_path, xml_path) = sheet_paths(&output);
+        Ok(Options {
+            input_dir,
+            image_path,
+            xml_path,
+            padding,
+            max_size,
+        })
+    }
+}
+
+/// The image and xml paths for `output`. A `.png` or
+/// `.xml` extension is dropped, other dots are part of
+/// the name, so `my.sheet` writes `my.sheet.png`.
+fn sheet_paths(output: &Path) -> (PathBuf, PathBuf) {
+    let has_sheet_extension =
+        output.extension().is_some_and(|extension| {
+            extension == "png" || extension == "xml"
+        });
+    let stem = if has_sheet_extension {
+        output.file_stem()
+    } else {
+        output.file_name()
+    }
+    .unwrap_or_default();
+    let with_extension = |extension: &str| {
+        let mut name = stem.to_os_string();
+        name.push(".");
+        name.push(extension);
+        output.with_file_name(name)
+    };
+    (with_extension("png"), with_extension("xml"))
+}
+
+fn parse_number(
+    flag: &str,
+    value: Option<String>,
+) -> Result<u32, String> {
+    value.and_then(|value| value.parse().ok()).ok_or_else(
+        || format!("{flag} expects a number\n{USAGE}"),
+    )
+}
+
+struct Sprite {
+    name: String,
+    image: RgbaImage,
+    x: u32,
+    y: u32,
+}
+
+fn main() -> ExitCode {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match pack(&options) {
+        Ok(count) => {
+            println!(
+                "packed {count} sprites into {}",
+                options.image_path.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn pack(
+    options: &Options,
+) -> Result<usize, Box<dyn Error>> {
+    let mut sprites = read_sprites(&options.input_dir)?;
+    if sprites.is_empty() {
+        return Err(format!(
+            "no png files found in {}",
+            options.input_dir.display()
+        )
+        .into());
+    }
+
+    let (width, height) = layout(
+        &mut sprites,
+        options.padding,
+        options.max_size,
+    )?;
+
+    let mut sheet = RgbaImage::new(width, height);
+    for sprite in &sprites {
+        imageops::replace(
+            &mut sheet,
+            &sprite.image,
+            sprite.x.into(),
+            sprite.y.into(),
+        );
+    }
+
+    let image_path = &options.image_path;
+    sheet.save(image_path)?;
+
+    // Kenney sheets list sub-textures alphabetically.
+    // This makes the xml the same however the directory
+    // is read, but adding or renaming a sprite still
+    // shifts the atlas index of every sprite after it.
+    sprites.sort_by(|a, b| a.name.cmp(&b.name));
+    let image_name = image_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    fs::write(
+        &options.xml_path,
+        to_kenney_xml(image_name, &sprites)?,
+    )?;
+
+    Ok(sprites.len())
+}
+
+fn read_sprites(
+    input_dir: &Path,
+) -> Result<Vec<Sprite>, Box<dyn Error>> {
+    let mut sprites = vec![];
+    for entry in fs::read_dir(input_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str())
+            != Some("png")
+        {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| {
+                format!(
+                    "{} is not valid utf-8",
+                    path.display()
+                )
+            })?
+            .to_string();
+        let image = image::open(&path)
+            .map_err(|error| {
+                format!("{}: {error}", path.display())
+            })?
+            .to_rgba8();
+        sprites.push(Sprite {
+            name,
+            image,
+            x: 0,
+            y: 0,
+        });
+    }
+    Ok(sprites)
+}
+
+/// Shelf packing: place the tallest sprites first, left
+/// to right, starting a new row when the current one is
+/// full. Returns the size of the resulting sheet.
+fn layout(
+    sprites: &mut [Sprite],
+    padding: u32,
+    max_size: u32,
+) -> Result<(u32, u32), Box<dyn Error>> {
+    sprites.sort_by(|a, b| {
+        b.image
+            .height()
+            .cmp(&a.image.height())
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    // aim for a roughly square sheet
+    let area: u64 = sprites
+        .iter()
+        .map(|sprite| {
+            u64::from(sprite.image.width() + padding)
+                * u64::from(sprite.image.height() + padding)
+        })
+        .sum();
+    let widest = sprites
+        .iter()
+        .map(|sprite| sprite.image.width() + padding * 2)
+        .max()
+        .unwrap_or_default();
+    let row_width = ((area as f64).sqrt().ceil() as u32)
+        .max(widest)
+        .min(max_size);
+
+    let mut x = padding;
+    let mut y = padding;
+    let mut row_height = 0;
+    let mut width = 0;
+
+    for sprite in sprites.iter_mut() {
+        let (sprite_width, sprite_height) =
+            sprite.image.dimensions();
+        if sprite_width + padding * 2 > max_size
+            || sprite_height + padding * 2 > max_size
+        {
+            return Err(format!(
+                "{} ({sprite_width}x{sprite_height}) does not fit in a {max_size}px sheet",
+                sprite.name
+            )
+            .into());
+        }
+        if x + sprite_width + padding > row_width {
+            x = padding;
+            y += row_height + padding;
+            row_height = 0;
+        }
+        sprite.x = x;
+        sprite.y = y;
+        x += sprite_width + padding;
+        row_height = row_height.max(sprite_height);
+        width = width.max(x);
+    }
+
+    let height = y + row_height + padding;
+    if height > max_size {
+        return Err(format!(
+            "sprites need a {width}x{height} sheet, which exceeds --max-size {max_size}"
+        )
+        .into());
+    }
+    Ok((width, height))
+}
+
+fn to_kenney_xml(
+    image_name: &str,
+    sprites: &[Sprite],
+) -> Result<String, std::fmt::Error> {
+    let mut xml = String::new();
+    writeln!(
+        xml,
+        r#"<TextureAtlas imagePath="{}">"#,
+        escape(image_name)
+    )?;
+    for sprite in sprites {
+        writeln!(
+            xml,
+            "\t<SubTexture name=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+            escape(&sprite.name),
+            sprite.x,
+            sprite.y,
+            sprite.image.width(),
+            sprite.image.height(),
+        )?;
+    }
+    writeln!(xml, "</TextureAtlas>")?;
+    Ok(xml)
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_paths_keep_dotted_names() {
+        for output in ["out/my.sheet.xml", "out/my.sheet"] {
+            assert_eq!(
+                sheet_paths(Path::new(output)),
+                (
+                    PathBuf::from("out/my.sheet.png"),
+                    PathBuf::from("out/my.sheet.xml"),
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn layout_places_sprites_apart_inside_the_sheet() {
+        let padding = 2;
+        let mut sprites: Vec<Sprite> = (0..40)
+            .map(|i| Sprite {
+                name: format!("sprite{i:02}"),
+                image: RgbaImage::new(
+                    5 + i * 7 % 60,
+                    5 + i * 13 % 45,
+                ),
+                x: 0,
+                y: 0,
+            })
+            .collect();
+        let (width, height) =
+            layout(&mut sprites, padding, 1024).unwrap();
+
+        for sprite in &sprites {
+            let (sprite_width, sprite_height) =
+                sprite.image.dimensions();
+            assert!(
+                sprite.x + sprite_width <= width
+                    && sprite.y + sprite_height <= height,
+                "{} is outside the {width}x{height} sheet",
+                sprite.name
+            );
+        }
+        for (i, a) in sprites.iter().enumerate() {
+            for b in &sprites[i + 1..] {
+                let apart = a.x + a.image.width() <= b.x
+                    || b.x + b.image.width() <= a.x
+                    || a.y + a.image.height() <= b.y
+                    || b.y + b.image.height() <= a.y;
+                assert!(
+                    apart,
+                    "{} overlaps {}",
+                    a.name, b.name
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Checks every hardcoded atlas index and sub-texture
+//! name in the game against the sheets in `assets/`,
+//! along with the nine-slice borders from each sheet's
+//! `.slices.json` sidecar.
+//!
+//! ```sh
+//! cargo run --bin validate_assets [assets_dir]
+//! ```
+//!
+//! Exits with a non-zero code if any reference is
+//! broken.
+
+use space_shooter::{
+    asset_references::{
+        all_sheet_references, validate, ValidationError,
+    },
+    kenney_assets::{
+        apply_slice_borders, parse_sub_textures, SubTexture,
+    },
+};
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+fn main() -> ExitCode {
+    let assets_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("assets")
+        });
+
+    let mut broken = 0;
+    for sheet in all_sheet_references() {
+        let xml_path = assets_dir.join(sheet.path);
+        match load_sheet(&xml_path) {
+            Ok(Sheet {
+                textures,
+                image_size,
+                unknown_slices,
+            }) => {
+                let mut errors = validate(
+                    &textures,
+                    image_size,
+                    &sheet.references,
+                );
+                for name in unknown_slices {
+                    errors.push(
+                        ValidationError::UnknownSlice {
+                            name,
+                        },
+                    );
+                }
+                if errors.is_empty() {
+                    println!(
+                        "{}: {} references ok",
+                        sheet.path,
+                        sheet.references.len()
+                    );
+                }
+                for error in &errors {
+                    eprintln!("{}: {error}", sheet.path);
+                }
+                broken += errors.len();
+            }
+            Err(error) => {
+                eprintln!("{}: {error}", sheet.path);
+                broken += 1;
+            }
+        }
+    }
+
+    if broken == 0 {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("{broken} broken asset references");
+        ExitCode::FAILURE
+    }
+}
+
+struct Sheet {
+    textures: Vec<SubTexture>,
+    image_size: (u32, u32),
+    /// names in the `.slices.json` sidecar that aren't
+    /// in the sheet
+    unknown_slices: Vec<String>,
+}
+
+/// Parse the sheet and its slices sidecar the same way
+/// `KenneySpriteSheetAssetLoader` does, without starting
+/// an app
+fn load_sheet(
+    xml_path: &Path,
+) -> Result<Sheet, Box<dyn Error>> {
+    let xml = fs::read_to_string(xml_path)?;
+    let mut textures = parse_sub_textures(&xml)?;
+    let image_path = xml_path.with_extension("png");
+    let image_size = image::image_dimensions(&image_path)?;
+    let unknown_slices = match fs::read(
+        image_path.with_extension("slices.json"),
+    ) {
+        Ok(json) => {
+            apply_slice_borders(&json, &mut textures)?
+        }
+        // most sheets don't have a sidecar
+        Err(error)
+            if error.kind() == io::ErrorKind::NotFound =>
+        {
+            vec![]
+        }
+        Err(error) => return Err(error.into()),
+    };
+    Ok(Sheet {
+        textures,
+        image_size,
+        unknown_slices,
+    })
+}
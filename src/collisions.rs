@@ -0,0 +1,439 @@
+//! Who can hit whom is declared once, as physics layers,
+//! so bevy_xpbd only reports the pairs we care about.
+//!
+//! Collisions are handled in two steps. The
+//! [`CollisionSet::Resolve`] step turns the frame's
+//! [`CollisionStarted`] events into
+//! [`ResolvedCollision`]s, consuming each projectile and
+//! target at most once, so one laser can't destroy two
+//! meteors and two lasers can't destroy the same meteor
+//! twice. [`Piercing`] projectiles are the exception and
+//! keep going after a hit. The [`CollisionSet::Respond`]
+//! step then despawns entities and sends the gameplay
+//! events.
+
+use bevy::{
+    ecs::query::{QueryData, QueryFilter},
+    prelude::*,
+    utils::HashSet,
+};
+use bevy_xpbd_2d::prelude::*;
+
+use crate::{
+    controls::{Laser, Piercing, Shooter},
+    meteors::{Meteor, MeteorDestroyed, MeteorType},
+    ship::{DeathCause, PlayerShipType, ShipDestroyed},
+    ufo::{Ufo, UfoDestroyed, UfoOwned},
+    ui::pause::Pausable,
+    GameState, Player,
+};
+
+pub struct CollisionsPlugin;
+
+impl Plugin for CollisionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ResolvedCollision>()
+            .configure_sets(
+                Update,
+                (
+                    CollisionSet::Resolve,
+                    CollisionSet::Respond,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(
+                        resource_equals(
+                            Pausable::NotPaused,
+                        ),
+                    ),
+            )
+            .add_systems(
+                Update,
+                (
+                    resolve_collisions
+                        .in_set(CollisionSet::Resolve),
+                    (
+                        meteor_laser_collision,
+                        ship_collision,
+                        ufo_laser_collision,
+                    )
+                        .in_set(CollisionSet::Respond),
+                ),
+            );
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CollisionSet {
+    /// decide which collisions count this frame
+    Resolve,
+    /// act on the resolved collisions
+    Respond,
+}
+
+/// A collision that counts. Within a frame every
+/// entity shows up in at most one of these, except for
+/// [`Piercing`] projectiles.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum ResolvedCollision {
+    LaserHitMeteor { laser: Entity, meteor: Entity },
+    LaserHitUfo { laser: Entity, ufo: Entity },
+    ShipHit { ship: Entity, hazard: Entity },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum GameLayer {
+    Player,
+    PlayerProjectile,
+    /// ufos
+    Enemy,
+    EnemyProjectile,
+    Meteor,
+}
+
+// written out rather than derived, the derive emits
+// `cfg(feature = "2d")` checks against this crate
+impl PhysicsLayer for GameLayer {
+    fn to_bits(&self) -> u32 {
+        1 << *self as u32
+    }
+    fn all_bits() -> u32 {
+        0b11111
+    }
+}
+
+impl GameLayer {
+    /// Puts an object on this layer, colliding only with
+    /// the layers that interact with it
+    pub fn collision_layers(self) -> CollisionLayers {
+        use GameLayer::*;
+        let filters: LayerMask = match self {
+            Player => {
+                [Enemy, EnemyProjectile, Meteor].into()
+            }
+            PlayerProjectile => [Enemy, Meteor].into(),
+            Enemy => [Player, PlayerProjectile].into(),
+            EnemyProjectile => [Player, Meteor].into(),
+            Meteor => {
+                [Player, PlayerProjectile, EnemyProjectile]
+                    .into()
+            }
+        };
+        CollisionLayers::new(self, filters)
+    }
+}
+
+/// Orders a colliding pair as `(first, second)` if one
+/// entity matches each query
+fn match_pair<D1, F1, D2, F2>(
+    CollisionStarted(a, b): &CollisionStarted,
+    first: &Query<D1, F1>,
+    second: &Query<D2, F2>,
+) -> Option<(Entity, Entity)>
+where
+    D1: QueryData,
+    F1: QueryFilter,
+    D2: QueryData,
+    F2: QueryFilter,
+{
+    if first.contains(*a) && second.contains(*b) {
+        Some((*a, *b))
+    } else if first.contains(*b) && second.contains(*a) {
+        Some((*b, *a))
+    } else {
+        None
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn resolve_collisions(
+    mut collisions: EventReader<CollisionStarted>,
+    mut resolved: EventWriter<ResolvedCollision>,
+    lasers: Query<Has<Piercing>, With<Laser>>,
+    meteors: Query<(), With<Meteor>>,
+    ufos: Query<(), With<Ufo>>,
+    player_ship: Query<(), With<Player>>,
+    hazards: Query<
+        (),
+        Or<(With<Meteor>, With<Ufo>, With<UfoOwned>)>,
+    >,
+    mut consumed: Local<HashSet<Entity>>,
+) {
+    consumed.clear();
+    for collision in collisions.read() {
+        if let Some((laser, target)) =
+            match_pair(collision, &lasers, &meteors)
+                .or_else(|| {
+                    match_pair(collision, &lasers, &ufos)
+                })
+        {
+            let piercing =
+                lasers.get(laser).unwrap_or(false);
+            if consumed.contains(&target)
+                || (!piercing && consumed.contains(&laser))
+            {
+                continue;
+            }
+            consumed.insert(target);
+            if !piercing {
+                consumed.insert(laser);
+            }
+            resolved.send(if meteors.contains(target) {
+                ResolvedCollision::LaserHitMeteor {
+                    laser,
+                    meteor: target,
+                }
+            } else {
+                ResolvedCollision::LaserHitUfo {
+                    laser,
+                    ufo: target,
+                }
+            });
+        } else if let Some((ship, hazard)) =
+            match_pair(collision, &player_ship, &hazards)
+        {
+            // a meteor that was shot this frame can't
+            // also destroy the ship
+            if consumed.contains(&ship)
+                || consumed.contains(&hazard)
+            {
+                continue;
+            }
+            consumed.insert(ship);
+            // ufo lasers are used up by the hit, meteors
+            // and ufos carry on
+            if lasers.contains(hazard) {
+                consumed.insert(hazard);
+            }
+            resolved.send(ResolvedCollision::ShipHit {
+                ship,
+                hazard,
+            });
+        }
+    }
+}
+
+pub fn meteor_laser_collision(
+    mut commands: Commands,
+    mut collisions: EventReader<ResolvedCollision>,
+    mut meteor_destroyed: EventWriter<MeteorDestroyed>,
+    lasers: Query<(&Shooter, Has<Piercing>), With<Laser>>,
+    meteors: Query<(&MeteorType, &Transform), With<Meteor>>,
+) {
+    for collision in collisions.read() {
+        let ResolvedCollision::LaserHitMeteor {
+            laser,
+            meteor,
+        } = *collision
+        else {
+            continue;
+        };
+        let (
+            Ok((shooter, piercing)),
+            Ok((meteor_type, transform)),
+        ) = (lasers.get(laser), meteors.get(meteor))
+        else {
+            continue;
+        };
+
+        if !piercing {
+            commands.entity(laser).despawn_recursive();
+        }
+        commands.entity(meteor).despawn_recursive();
+
+        meteor_destroyed.send(MeteorDestroyed {
+            destroyed_at: *transform,
+            destroyed_type: *meteor_type,
+            destroyed_by: *shooter,
+        });
+    }
+}
+
+pub fn ufo_laser_collision(
+    mut commands: Commands,
+    mut collisions: EventReader<ResolvedCollision>,
+    mut ufo_destroyed: EventWriter<UfoDestroyed>,
+    lasers: Query<(&Shooter, Has<Piercing>), With<Laser>>,
+    ufos: Query<&Transform, With<Ufo>>,
+) {
+    for collision in collisions.read() {
+        let ResolvedCollision::LaserHitUfo { laser, ufo } =
+            *collision
+        else {
+            continue;
+        };
+        let (Ok((shooter, piercing)), Ok(transform)) =
+            (lasers.get(laser), ufos.get(ufo))
+        else {
+            continue;
+        };
+
+        if !piercing {
+            commands.entity(laser).despawn_recursive();
+        }
+        commands.entity(ufo).despawn_recursive();
+
+        ufo_destroyed.send(UfoDestroyed {
+            destroyed_at: *transform,
+            destroyed_by: *shooter,
+        });
+    }
+}
+
+pub fn ship_collision(
+    mut commands: Commands,
+    mut collisions: EventReader<ResolvedCollision>,
+    mut ship_destroyed: EventWriter<ShipDestroyed>,
+    hazards: Query<(Has<Meteor>, Has<Laser>)>,
+    player_ship: Query<
+        (&Transform, &PlayerShipType),
+        With<Player>,
+    >,
+) {
+    for collision in collisions.read() {
+        let ResolvedCollision::ShipHit { ship, hazard } =
+            *collision
+        else {
+            continue;
+        };
+        let (
+            Ok((transform, ship_type)),
+            Ok((is_meteor, is_laser)),
+        ) = (player_ship.get(ship), hazards.get(hazard))
+        else {
+            continue;
+        };
+
+        commands.entity(ship).despawn_recursive();
+
+        let cause = if is_meteor {
+            DeathCause::Meteor
+        } else if is_laser {
+            commands.entity(hazard).despawn_recursive();
+            DeathCause::UfoLaser
+        } else {
+            DeathCause::Ufo
+        };
+
+        ship_destroyed.send(ShipDestroyed {
+            destroyed_at: *transform,
+            ship_type: ship_type.clone(),
+            cause,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controls::PlayerId;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_event::<CollisionStarted>()
+            .add_event::<ResolvedCollision>()
+            .add_event::<MeteorDestroyed>()
+            .add_systems(
+                Update,
+                (
+                    resolve_collisions,
+                    meteor_laser_collision,
+                )
+                    .chain(),
+            );
+        app
+    }
+
+    fn spawn_laser(
+        app: &mut App,
+        piercing: bool,
+    ) -> Entity {
+        let mut laser = app.world.spawn((
+            Laser {
+                movement_factor: Vec2::ZERO,
+                speed: 0.,
+            },
+            Shooter::Player(PlayerId(0)),
+        ));
+        if piercing {
+            laser.insert(Piercing);
+        }
+        laser.id()
+    }
+
+    fn spawn_meteor(app: &mut App) -> Entity {
+        app.world
+            .spawn((
+                Meteor,
+                MeteorType::Big,
+                Transform::default(),
+            ))
+            .id()
+    }
+
+    fn collide(app: &mut App, a: Entity, b: Entity) {
+        app.world.send_event(CollisionStarted(a, b));
+    }
+
+    fn meteors_destroyed(app: &App) -> usize {
+        app.world
+            .resource::<Events<MeteorDestroyed>>()
+            .len()
+    }
+
+    #[test]
+    fn laser_destroys_one_of_two_meteors() {
+        let mut app = app();
+        let laser = spawn_laser(&mut app, false);
+        let first = spawn_meteor(&mut app);
+        let second = spawn_meteor(&mut app);
+        collide(&mut app, laser, first);
+        collide(&mut app, second, laser);
+
+        app.update();
+
+        assert_eq!(meteors_destroyed(&app), 1);
+        assert!(app.world.get_entity(laser).is_none());
+        assert!(app.world.get_entity(first).is_none());
+        assert!(app.world.get_entity(second).is_some());
+    }
+
+    #[test]
+    fn two_lasers_destroy_a_meteor_once() {
+        let mut app = app();
+        let first = spawn_laser(&mut app, false);
+        let second = spawn_laser(&mut app, false);
+        let meteor = spawn_meteor(&mut app);
+        collide(&mut app, first, meteor);
+        collide(&mut app, meteor, second);
+
+        app.update();
+
+        // meteors split once per MeteorDestroyed, so this
+        // is also exactly one split
+        assert_eq!(meteors_destroyed(&app), 1);
+        assert!(app.world.get_entity(meteor).is_none());
+        assert!(app.world.get_entity(first).is_none());
+        assert!(app.world.get_entity(second).is_some());
+    }
+
+    #[test]
+    fn piercing_laser_hits_both_meteors() {
+        let mut app = app();
+        let laser = spawn_laser(&mut app, true);
+        let first = spawn_meteor(&mut app);
+        let second = spawn_meteor(&mut app);
+        collide(&mut app, laser, first);
+        collide(&mut app, second, laser);
+
+        app.update();
+
+        assert_eq!(meteors_destroyed(&app), 2);
+        assert!(app.world.get_entity(laser).is_some());
+        assert!(app.world.get_entity(first).is_none());
+        assert!(app.world.get_entity(second).is_none());
+    }
+}
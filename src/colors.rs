@@ -1,3 +1,7 @@
 use bevy::prelude::Color;
 
 pub const TEXT: Color = Color::BLACK;
+
+/// Outlines the menu item focused with the keyboard or
+/// a gamepad
+pub const FOCUS: Color = Color::rgb(1.0, 0.75, 0.2);
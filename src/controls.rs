@@ -1,11 +1,13 @@
 use crate::{
     assets::ImageAssets,
+    collisions::GameLayer,
     kenney_assets::KenneySpriteSheetAsset,
-    ship::{PlayerEngineFire, PlayerShipType},
+    settings::GameSettings,
+    ship::{PlayerEngineFire, PlayerShipType, ShipColor},
     ui::pause::Pausable,
     GameState, Player,
 };
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_xpbd_2d::prelude::*;
 use std::time::Duration;
 
@@ -13,44 +15,70 @@ pub struct ControlsPlugin;
 
 impl Plugin for ControlsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<MovementFactor>().add_systems(
-            Update,
-            (
-                player_movement_system
-                    .run_if(in_state(GameState::Playing)),
-                weapon_system
-                    .run_if(in_state(GameState::Playing)),
-                engine_fire
-                    .run_if(in_state(GameState::Playing)),
-                laser_movement,
-            )
-                .run_if(resource_equals(
-                    Pausable::NotPaused,
-                )),
-        );
+        app.init_resource::<MovementFactor>()
+            .add_event::<LaserMissed>()
+            .add_event::<LaserFired>()
+            .add_systems(
+                Update,
+                (
+                    player_movement_system.run_if(
+                        in_state(GameState::Playing),
+                    ),
+                    weapon_system.run_if(in_state(
+                        GameState::Playing,
+                    )),
+                    engine_fire.run_if(in_state(
+                        GameState::Playing,
+                    )),
+                    (
+                        laser_movement,
+                        despawn_offscreen_lasers,
+                    )
+                        .chain(),
+                )
+                    .run_if(
+                        resource_equals(
+                            Pausable::NotPaused,
+                        ),
+                    ),
+            );
     }
 }
 
 #[derive(Component)]
-pub struct Laser{
+pub struct Laser {
     /// movement factor is ship's movement speed at time of firing
-pub    movement_factor: Vec2,
-/// speed is laser's inherent movement speed
-pub speed: f32,
+    pub movement_factor: Vec2,
+    /// speed is laser's inherent movement speed
+    pub speed: f32,
 }
 
+/// A projectile that keeps going after a hit, instead
+/// of being used up by the first thing it hits
+#[derive(Component, Debug)]
+pub struct Piercing;
+
 #[derive(Resource, Default, Deref, DerefMut)]
 pub struct MovementFactor(pub Vec2);
 
+/// A player laser left the screen without hitting
+/// anything
+#[derive(Debug, Event)]
+pub struct LaserMissed;
+
+/// The player fired a laser
+#[derive(Debug, Event)]
+pub struct LaserFired;
+
 fn laser_movement(
     mut lasers: Query<(&mut Transform, &Laser)>,
     time: Res<Time>,
 ) {
     for (
         mut transform,
-        Laser{
+        Laser {
             movement_factor,
-            speed
+            speed,
         },
     ) in &mut lasers
     {
@@ -65,23 +93,82 @@ fn laser_movement(
     }
 }
 
+/// Lasers don't wrap around the screen, once they
+/// leave it they're gone
+fn despawn_offscreen_lasers(
+    mut commands: Commands,
+    lasers: Query<
+        (Entity, &Transform, Has<PlayerOwned>),
+        With<Laser>,
+    >,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut missed: EventWriter<LaserMissed>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    // a little margin so lasers are fully offscreen
+    // before they disappear
+    let width = window.resolution.width() / 2. + 50.;
+    let height = window.resolution.height() / 2. + 50.;
+    for (entity, transform, player_owned) in &lasers {
+        if transform.translation.x.abs() > width
+            || transform.translation.y.abs() > height
+        {
+            commands.entity(entity).despawn_recursive();
+            if player_owned {
+                missed.send(LaserMissed);
+            }
+        }
+    }
+}
+
 #[derive(Component)]
 pub struct PlayerOwned;
 
+/// Identifies one player, so kills can be credited to
+/// whoever made them. There's only one player for now.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash,
+)]
+pub struct PlayerId(pub usize);
+
+/// Who fired a projectile. Carried into the destruction
+/// events so only the shooter is credited with the kill.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shooter {
+    Player(PlayerId),
+    Ufo,
+}
+
+impl Shooter {
+    /// The player to credit, if a player fired the shot
+    pub fn player(&self) -> Option<PlayerId> {
+        match self {
+            Shooter::Player(id) => Some(*id),
+            Shooter::Ufo => None,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn weapon_system(
     mut commands: Commands,
     time: Res<Time>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    query: Query<&Transform, With<Player>>,
+    settings: Res<GameSettings>,
+    query: Query<(&Transform, &ShipColor), With<Player>>,
     movement_factor: ResMut<MovementFactor>,
     images: Res<ImageAssets>,
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
     mut last_shot: Local<Option<Duration>>,
+    mut fired: EventWriter<LaserFired>,
 ) {
     let space_sheet =
         sheets.get(&images.space_sheet).unwrap();
 
-    let Ok(transform) = query.get_single() else {
+    let Ok((transform, ship_color)) = query.get_single()
+    else {
         if query.iter().count() > 1 {
             error_once!(
                 "Only expected one Player component. got {}",
@@ -91,7 +178,7 @@ fn weapon_system(
         return;
     };
 
-    if keyboard_input.pressed(KeyCode::Space) {
+    if keyboard_input.pressed(settings.controls.fire) {
         let can_shoot = last_shot.is_none() || {
             if let Some(shot) = *last_shot {
                 time.elapsed() - shot
@@ -114,19 +201,24 @@ fn weapon_system(
                     layout: space_sheet
                         .texture_atlas_layout
                         .clone(),
-                    index: 105,
+                    index: ship_color
+                        .laser_atlas_index(space_sheet),
                 },
-                Laser{
+                Laser {
                     movement_factor: **movement_factor,
-                    speed: 1000.
+                    speed: 1000.,
                 },
                 PlayerOwned,
+                Shooter::Player(PlayerId::default()),
                 Collider::triangle(
                     Vec2::new(0., -27.),
                     Vec2::new(4.5, 27.),
                     Vec2::new(-4.5, 27.),
                 ),
+                GameLayer::PlayerProjectile
+                    .collision_layers(),
             ));
+            fired.send(LaserFired);
         }
     }
 }
@@ -137,8 +229,9 @@ fn engine_fire(
         With<PlayerEngineFire>,
     >,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
 ) {
-    if keyboard_input.pressed(KeyCode::ArrowUp) {
+    if keyboard_input.pressed(settings.controls.thrust) {
         for mut visibility in query.iter_mut() {
             *visibility = Visibility::Visible;
         }
@@ -151,6 +244,7 @@ fn engine_fire(
 fn player_movement_system(
     time: Res<Time>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<GameSettings>,
     mut query: Query<
         (&mut Transform, &PlayerShipType),
         With<Player>,
@@ -162,19 +256,20 @@ fn player_movement_system(
         if query.iter().count() > 1 {
             error_once!(
                 "Expected zero or one Player component. got {}",
-                query.iter().count()    
+                query.iter().count()
             );
         }
         return;
     };
 
+    let controls = &settings.controls;
     let mut rotation_factor = 0.0;
 
-    if keyboard_input.pressed(KeyCode::ArrowLeft) {
+    if keyboard_input.pressed(controls.rotate_left) {
         rotation_factor += 1.0;
     }
 
-    if keyboard_input.pressed(KeyCode::ArrowRight) {
+    if keyboard_input.pressed(controls.rotate_right) {
         rotation_factor -= 1.0;
     }
 
@@ -192,7 +287,7 @@ fn player_movement_system(
     let user_facing_direction =
         transform.rotation * Vec3::Y;
 
-    if keyboard_input.pressed(KeyCode::ArrowUp) {
+    if keyboard_input.pressed(controls.thrust) {
         movement_factor.0 = (movement_factor.0
             + 0.01 * user_facing_direction.xy())
         .clamp(Vec2::splat(-1.0), Vec2::splat(1.0));
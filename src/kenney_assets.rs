@@ -2,43 +2,198 @@ use bevy::{asset::LoadedAsset, utils::thiserror};
 use bevy::{
     asset::{
         io::Reader, AssetLoader, AsyncReadExt, LoadContext,
+        LoadDirectError, ReadAssetBytesError,
     },
     prelude::*,
     reflect::TypePath,
     utils::BoxedFuture,
 };
+use std::{path::PathBuf, time::Duration};
 use thiserror::Error;
 
+mod hot_reload;
+mod json;
+mod slices;
+
+pub use hot_reload::KenneySpriteSheetReloaded;
+pub use json::{
+    AsepriteSpriteSheetAssetLoader,
+    TexturePackerSpriteSheetAssetLoader,
+};
+pub use slices::apply_slice_borders;
+
 /// Kenney makes [amazing assets](https://kenney.nl/).
 ///
 /// Often these assets come with a spritesheet and
 /// an xml file describing said spritesheet.
+///
+/// TexturePacker (`.tp.json`) and Aseprite
+/// (`.aseprite.json`) exports are loaded into the same
+/// [`KenneySpriteSheetAsset`] shape.
+///
+/// With the `hot_reload` feature, edits to a sheet are
+/// picked up while the game is running.
 pub struct KenneyAssetPlugin;
 
 impl Plugin for KenneyAssetPlugin {
     fn build(&self, app: &mut App) {
         app
           .init_asset::<KenneySpriteSheetAsset>()
-          .init_asset_loader::<KenneySpriteSheetAssetLoader>();
+          .init_asset_loader::<KenneySpriteSheetAssetLoader>()
+          .init_asset_loader::<TexturePackerSpriteSheetAssetLoader>()
+          .init_asset_loader::<AsepriteSpriteSheetAssetLoader>()
+          .init_resource::<hot_reload::SheetNames>()
+          .add_event::<KenneySpriteSheetReloaded>()
+          .add_systems(
+              PreUpdate,
+              (
+                  hot_reload::track_sprite_sheet_changes,
+                  hot_reload::remap_texture_atlases,
+              )
+                  .chain(),
+          );
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SubTexture {
     pub name: String,
+    /// x position of the packed rect in the sheet
     pub x: u32,
+    /// y position of the packed rect in the sheet
     pub y: u32,
+    /// width of the frame, before any packing rotation
     pub width: u32,
+    /// height of the frame, before any packing rotation
     pub height: u32,
+    /// the packer stored this frame rotated 90°
+    /// clockwise, so it occupies `height` x `width`
+    /// pixels in the sheet
+    pub rotated: bool,
+    /// set when the packer trimmed transparent pixels
+    /// off the original frame
+    pub trim: Option<Trim>,
+    /// how long this frame is shown for. Only Aseprite
+    /// exports carry durations.
+    pub duration: Option<Duration>,
+    /// Nine-slice borders, in pixels, for sub-textures
+    /// that can be stretched (panels, buttons, bars)
+    pub slice: Option<BorderRect>,
+}
+
+impl SubTexture {
+    /// The area this sub-texture occupies in the sheet
+    pub fn rect(&self) -> Rect {
+        let (width, height) = if self.rotated {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        Rect::from_corners(
+            Vec2::new(self.x as f32, self.y as f32),
+            Vec2::new(
+                (self.x + width) as f32,
+                (self.y + height) as f32,
+            ),
+        )
+    }
+}
+
+/// Where a trimmed frame sits inside its original,
+/// untrimmed image.
+#[derive(Debug, Clone, Copy)]
+pub struct Trim {
+    /// offset of the trimmed frame from the top-left
+    /// of the original image
+    pub offset: UVec2,
+    /// size of the original image
+    pub source_size: UVec2,
+}
+
+/// A named range of frames, as exported by Aseprite
+#[derive(Debug, Clone)]
+pub struct FrameTag {
+    pub name: String,
+    /// index of the first frame in the tag
+    pub from: usize,
+    /// index of the last frame in the tag, inclusive
+    pub to: usize,
+    pub direction: TagDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagDirection {
+    Forward,
+    Reverse,
+    PingPong,
+    PingPongReverse,
+}
+
+/// The nine pieces of a sliced sub-texture, each with
+/// its own rect in the `texture_atlas_layout`
+#[derive(Debug, Clone)]
+pub struct NineSlice {
+    /// index of the sliced sub-texture in `textures`
+    pub texture_index: usize,
+    pub border: BorderRect,
+    /// atlas indices of the pieces, row by row starting
+    /// from the top left
+    pub pieces: [usize; 9],
 }
 
 #[derive(Asset, TypePath, Debug)]
 pub struct KenneySpriteSheetAsset {
     pub textures: Vec<SubTexture>,
+    /// Frame tags, only present in Aseprite exports
+    pub tags: Vec<FrameTag>,
+    /// Pieces of every sub-texture with slice borders.
+    /// Their atlas indices come after all `textures`, so
+    /// slicing never shifts a sub-texture's index.
+    pub nine_slices: Vec<NineSlice>,
     pub sheet: Handle<Image>,
     pub texture_atlas_layout: Handle<TextureAtlasLayout>,
 }
 
+impl KenneySpriteSheetAsset {
+    /// The atlas index of the sub-texture called `name`
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.textures
+            .iter()
+            .position(|texture| texture.name == name)
+    }
+
+    /// The nine-slice pieces of the sub-texture called
+    /// `name`, if it has slice borders
+    pub fn nine_slice(
+        &self,
+        name: &str,
+    ) -> Option<&NineSlice> {
+        let index = self.index_of(name)?;
+        self.nine_slices
+            .iter()
+            .find(|slice| slice.texture_index == index)
+    }
+
+    /// A name for every index in the atlas layout,
+    /// including nine-slice pieces (`glassPanel.png#4`)
+    pub fn atlas_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .textures
+            .iter()
+            .map(|texture| texture.name.clone())
+            .collect();
+        for slice in &self.nine_slices {
+            let name =
+                &self.textures[slice.texture_index].name;
+            names
+                .extend((0..9).map(|piece| {
+                    format!("{name}#{piece}")
+                }));
+        }
+        names
+    }
+}
+
 #[derive(Default)]
 pub struct KenneySpriteSheetAssetLoader;
 
@@ -50,6 +205,201 @@ pub enum KenneySpriteSheetAssetLoaderError {
     /// An [IO](std::io) Error
     #[error("Could not load asset: {0}")]
     Io(#[from] std::io::Error),
+    /// The spritesheet image failed to load
+    #[error("Could not load spritesheet image: {0}")]
+    Image(#[from] Box<LoadDirectError>),
+    /// The file next to the descriptor wasn't an image
+    #[error("{0:?} is not an image")]
+    NotAnImage(PathBuf),
+    /// Invalid Kenney/Sparrow XML
+    #[error("Could not parse xml: {0}")]
+    Xml(#[from] roxmltree::Error),
+    /// Invalid TexturePacker or Aseprite JSON
+    #[error("Could not parse json: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The `.slices.json` sidecar couldn't be read
+    #[error("Could not read slices sidecar: {0}")]
+    SlicesSidecar(#[from] ReadAssetBytesError),
+    /// A `SubTexture` was missing a required attribute
+    /// or it could not be parsed
+    #[error("SubTexture {name:?} has a missing or invalid `{attribute}` attribute")]
+    InvalidAttribute {
+        name: String,
+        attribute: &'static str,
+    },
+}
+
+/// Loads the spritesheet image at `image_path` and
+/// builds the `texture_atlas_layout` labeled asset for
+/// `textures`. Shared by every spritesheet format.
+///
+/// Slice borders from an optional
+/// `<sheet>.slices.json` sidecar are applied here too.
+async fn finish_sprite_sheet(
+    load_context: &mut LoadContext<'_>,
+    image_path: PathBuf,
+    mut textures: Vec<SubTexture>,
+    tags: Vec<FrameTag>,
+) -> Result<
+    KenneySpriteSheetAsset,
+    KenneySpriteSheetAssetLoaderError,
+> {
+    let image = load_context
+        .load_direct(image_path.clone())
+        .await
+        .map_err(Box::new)?;
+    let spritesheet_image: &Image =
+        image.get().ok_or_else(|| {
+            KenneySpriteSheetAssetLoaderError::NotAnImage(
+                image_path.clone(),
+            )
+        })?;
+    let mut layout = TextureAtlasLayout::new_empty(
+        spritesheet_image.size_f32(),
+    );
+    for texture in &textures {
+        layout.add_texture(texture.rect());
+    }
+
+    slices::apply_sidecar(
+        load_context,
+        image_path.with_extension("slices.json"),
+        &mut textures,
+    )
+    .await?;
+    let nine_slices =
+        slices::add_nine_slices(&textures, &mut layout);
+
+    let sheet_handle: Handle<Image> =
+        load_context.load(image_path);
+    let texture_atlas_layout = LoadedAsset::from(layout);
+    let layout_handle = load_context
+        .add_loaded_labeled_asset(
+            "texture_atlas_layout",
+            texture_atlas_layout,
+        );
+    Ok(KenneySpriteSheetAsset {
+        textures,
+        tags,
+        nine_slices,
+        sheet: sheet_handle,
+        texture_atlas_layout: layout_handle,
+    })
+}
+
+/// Parses the `<SubTexture>` elements of a Kenney
+/// spritesheet. The optional Sparrow attributes
+/// (`rotated`, `frameX`, `frameY`, `frameWidth`,
+/// `frameHeight`) are understood as well, as are
+/// nine-slice borders (`sliceLeft`, `sliceRight`,
+/// `sliceTop`, `sliceBottom`).
+pub fn parse_sub_textures(
+    xml: &str,
+) -> Result<
+    Vec<SubTexture>,
+    KenneySpriteSheetAssetLoaderError,
+> {
+    let doc = roxmltree::Document::parse(xml)?;
+
+    doc.descendants()
+        .filter(|element| {
+            element.tag_name() == "SubTexture".into()
+        })
+        .map(parse_sub_texture)
+        .collect()
+}
+
+fn parse_sub_texture(
+    tex: roxmltree::Node,
+) -> Result<SubTexture, KenneySpriteSheetAssetLoaderError> {
+    let name = tex
+        .attribute("name")
+        .unwrap_or_default()
+        .to_string();
+    let invalid = |attribute: &'static str| {
+        KenneySpriteSheetAssetLoaderError::InvalidAttribute {
+            name: name.clone(),
+            attribute,
+        }
+    };
+    let optional = |attribute: &'static str| {
+        tex.attribute(attribute)
+            .map(|value| {
+                value
+                    .parse::<i32>()
+                    .map_err(|_| invalid(attribute))
+            })
+            .transpose()
+    };
+    let required = |attribute: &'static str| {
+        optional(attribute)?
+            .and_then(|value| u32::try_from(value).ok())
+            .ok_or_else(|| invalid(attribute))
+    };
+
+    let x = required("x")?;
+    let y = required("y")?;
+    let rotated = tex.attribute("rotated") == Some("true");
+    // Sparrow gives the size of the rect in the sheet,
+    // which is swapped for rotated frames
+    let (width, height) = if rotated {
+        (required("height")?, required("width")?)
+    } else {
+        (required("width")?, required("height")?)
+    };
+
+    // Sparrow stores the trim as a negative offset of
+    // the frame relative to the sprite
+    let trim = match (
+        optional("frameX")?,
+        optional("frameY")?,
+        optional("frameWidth")?,
+        optional("frameHeight")?,
+    ) {
+        (
+            Some(frame_x),
+            Some(frame_y),
+            Some(frame_width),
+            Some(frame_height),
+        ) => Some(Trim {
+            offset: UVec2::new(
+                frame_x.unsigned_abs(),
+                frame_y.unsigned_abs(),
+            ),
+            source_size: UVec2::new(
+                frame_width.unsigned_abs(),
+                frame_height.unsigned_abs(),
+            ),
+        }),
+        _ => None,
+    };
+
+    let slice = match (
+        optional("sliceLeft")?,
+        optional("sliceRight")?,
+        optional("sliceTop")?,
+        optional("sliceBottom")?,
+    ) {
+        (None, None, None, None) => None,
+        (left, right, top, bottom) => Some(BorderRect {
+            left: left.unwrap_or_default() as f32,
+            right: right.unwrap_or_default() as f32,
+            top: top.unwrap_or_default() as f32,
+            bottom: bottom.unwrap_or_default() as f32,
+        }),
+    };
+
+    Ok(SubTexture {
+        name,
+        x,
+        y,
+        width,
+        height,
+        rotated,
+        trim,
+        duration: None,
+        slice,
+    })
 }
 
 impl AssetLoader for KenneySpriteSheetAssetLoader {
@@ -69,96 +419,20 @@ impl AssetLoader for KenneySpriteSheetAssetLoader {
                 load_context.asset_path().path();
             let image_path =
                 original_path.with_extension("png");
-            let image = load_context
-                .load_direct(image_path.clone())
-                .await
-                // todo: .unwrap
-                .unwrap();
-            let sheet_handle: Handle<Image> =
-                load_context.load(image_path);
-
-            let spritesheet_image: &Image =
-                image.get().unwrap();
-            let spritesheet_size =
-                spritesheet_image.size_f32();
 
             let mut xml_string = String::new();
-            reader
-                .read_to_string(&mut xml_string)
-                .await
-                .unwrap();
-
-            let doc =
-                roxmltree::Document::parse(&xml_string)
-                    .unwrap();
-
-            let space_sheet_dimensions = Vec2::new(
-                spritesheet_size.x,
-                spritesheet_size.y,
-            );
-
-            let mut layout = TextureAtlasLayout::new_empty(
-                space_sheet_dimensions,
-            );
-            let sub_textures: Vec<SubTexture> = doc
-                .descendants()
-                .filter(|element| {
-                    element.tag_name()
-                        == "SubTexture".into()
-                })
-                .map(|tex| {
-                    let x: u32 = tex
-                        .attribute("x")
-                        .unwrap()
-                        .parse()
-                        .unwrap();
-                    let y: u32 = tex
-                        .attribute("y")
-                        .unwrap()
-                        .parse()
-                        .unwrap();
-                    let width: u32 = tex
-                        .attribute("width")
-                        .unwrap()
-                        .parse()
-                        .unwrap();
-                    let height: u32 = tex
-                        .attribute("height")
-                        .unwrap()
-                        .parse()
-                        .unwrap();
-
-                    layout.add_texture(Rect::from_corners(
-                        Vec2::new(x as f32, y as f32),
-                        Vec2::new(
-                            (x + width) as f32,
-                            (y + height) as f32,
-                        ),
-                    ));
-                    SubTexture {
-                        name: tex
-                            .attribute("name")
-                            .unwrap()
-                            .to_string(),
-                        x,
-                        y,
-                        width,
-                        height,
-                    }
-                })
-                .collect();
-            let texture_atlas_layout =
-                LoadedAsset::from(layout);
-            let layout_handle = load_context
-                .add_loaded_labeled_asset(
-                    "texture_atlas_layout",
-                    texture_atlas_layout,
-                );
-            Ok(KenneySpriteSheetAsset {
-                textures: sub_textures,
-                sheet: sheet_handle,
-                texture_atlas_layout: layout_handle,
-            })
+            reader.read_to_string(&mut xml_string).await?;
+
+            let sub_textures =
+                parse_sub_textures(&xml_string)?;
+
+            finish_sprite_sheet(
+                load_context,
+                image_path,
+                sub_textures,
+                vec![],
+            )
+            .await
         })
     }
 
@@ -166,3 +440,23 @@ impl AssetLoader for KenneySpriteSheetAssetLoader {
         &["xml"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotated_sparrow_sub_texture() {
+        let xml = r#"<TextureAtlas imagePath="sheet.png">
+            <SubTexture name="ship.png" x="2" y="4" width="20" height="10" rotated="true"/>
+        </TextureAtlas>"#;
+        let textures = parse_sub_textures(xml).unwrap();
+
+        let ship = &textures[0];
+        assert_eq!((ship.width, ship.height), (10, 20));
+        assert_eq!(
+            ship.rect(),
+            Rect::new(2., 4., 22., 14.)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Sprite sheets can be edited while the game is running.
+//!
+//! When the xml (or the png or `.slices.json` next to
+//! it) changes, the sheet is reloaded and its
+//! `texture_atlas_layout` is rebuilt in place. The loader
+//! reads the png and sidecar through the
+//! [`LoadContext`](bevy::asset::LoadContext), which makes
+//! them dependencies of the sheet, so the asset server
+//! reloads it on its own. Sub-textures may have moved, so
+//! every live [`TextureAtlas`] using the sheet is pointed
+//! back at the sub-texture with the same name.
+
+use bevy::{prelude::*, utils::HashMap};
+
+use super::KenneySpriteSheetAsset;
+
+/// Sent after a sheet was reloaded, with the old to new
+/// atlas index mapping, so anything else holding indices
+/// (like animation clips) can follow along.
+#[derive(Debug, Event)]
+pub struct KenneySpriteSheetReloaded {
+    pub sheet: AssetId<KenneySpriteSheetAsset>,
+    /// `remap[old_index]` is the index of the sub-texture
+    /// with the same name in the reloaded sheet, or
+    /// `None` if it was removed
+    pub remap: Vec<Option<usize>>,
+}
+
+impl KenneySpriteSheetReloaded {
+    pub fn new_index(
+        &self,
+        old_index: usize,
+    ) -> Option<usize> {
+        self.remap.get(old_index).copied().flatten()
+    }
+}
+
+/// The atlas names of every loaded sheet, as of the
+/// last time it was loaded
+#[derive(Resource, Default)]
+pub(super) struct SheetNames(
+    HashMap<AssetId<KenneySpriteSheetAsset>, Vec<String>>,
+);
+
+pub(super) fn track_sprite_sheet_changes(
+    mut events: EventReader<
+        AssetEvent<KenneySpriteSheetAsset>,
+    >,
+    sheets: Res<Assets<KenneySpriteSheetAsset>>,
+    mut sheet_names: ResMut<SheetNames>,
+    mut reloaded: EventWriter<KenneySpriteSheetReloaded>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id } => {
+                let Some(sheet) = sheets.get(*id) else {
+                    continue;
+                };
+                let new_names = sheet.atlas_names();
+                let Some(old_names) = sheet_names
+                    .0
+                    .insert(*id, new_names.clone())
+                else {
+                    continue;
+                };
+
+                let new_indices: HashMap<&str, usize> =
+                    new_names
+                        .iter()
+                        .enumerate()
+                        .map(|(index, name)| {
+                            (name.as_str(), index)
+                        })
+                        .collect();
+                let remap: Vec<Option<usize>> = old_names
+                    .iter()
+                    .map(|name| {
+                        new_indices
+                            .get(name.as_str())
+                            .copied()
+                    })
+                    .collect();
+
+                info!(
+                    "reloaded spritesheet {:?} with {} sub-textures",
+                    id,
+                    sheet.textures.len()
+                );
+                reloaded.send(KenneySpriteSheetReloaded {
+                    sheet: *id,
+                    remap,
+                });
+            }
+            AssetEvent::Removed { id } => {
+                sheet_names.0.remove(id);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn remap_texture_atlases(
+    mut events: EventReader<KenneySpriteSheetReloaded>,
+    sheets: Res<Assets<KenneySpriteSheetAsset>>,
+    mut atlases: Query<(Entity, &mut TextureAtlas)>,
+) {
+    for event in events.read() {
+        let Some(sheet) = sheets.get(event.sheet) else {
+            continue;
+        };
+        for (entity, mut atlas) in &mut atlases {
+            if atlas.layout != sheet.texture_atlas_layout {
+                continue;
+            }
+            match event.new_index(atlas.index) {
+                Some(index) => {
+                    if atlas.index != index {
+                        atlas.index = index;
+                    }
+                }
+                None => {
+                    warn!(
+                        "{:?} uses atlas index {} which no longer exists after reloading the spritesheet",
+                        entity, atlas.index
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,362 @@
+//! TexturePacker and Aseprite both export a JSON
+//! description of a packed sheet. The two formats share
+//! the same `frames`/`meta` layout, with Aseprite adding
+//! per-frame durations and `meta.frameTags`.
+//!
+//! `frames` can either be an object keyed by frame name
+//! ("hash") or an array of frames carrying a `filename`
+//! ("array").
+
+use bevy::{
+    asset::{
+        io::Reader, AssetLoader, AsyncReadExt, LoadContext,
+    },
+    prelude::*,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+use super::{
+    finish_sprite_sheet, FrameTag, KenneySpriteSheetAsset,
+    KenneySpriteSheetAssetLoaderError, SubTexture,
+    TagDirection, Trim,
+};
+
+#[derive(Deserialize)]
+struct SpriteSheetJson {
+    frames: Frames,
+    meta: Meta,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Frames {
+    Array(Vec<NamedFrame>),
+    // serde_json's `preserve_order` keeps the frames in
+    // file order, which Aseprite tags index into.
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Deserialize)]
+struct NamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: Frame,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Frame {
+    frame: JsonRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    sprite_source_size: Option<JsonRect>,
+    source_size: Option<JsonSize>,
+    /// milliseconds
+    duration: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct JsonRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    image: String,
+    #[serde(default)]
+    frame_tags: Vec<JsonFrameTag>,
+}
+
+#[derive(Deserialize)]
+struct JsonFrameTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: JsonTagDirection,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum JsonTagDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+impl From<JsonTagDirection> for TagDirection {
+    fn from(direction: JsonTagDirection) -> Self {
+        match direction {
+            JsonTagDirection::Forward => {
+                TagDirection::Forward
+            }
+            JsonTagDirection::Reverse => {
+                TagDirection::Reverse
+            }
+            JsonTagDirection::Pingpong => {
+                TagDirection::PingPong
+            }
+            JsonTagDirection::PingpongReverse => {
+                TagDirection::PingPongReverse
+            }
+        }
+    }
+}
+
+impl Frame {
+    fn into_sub_texture(self, name: String) -> SubTexture {
+        // TexturePacker reports `frame.w`/`frame.h` as
+        // the unrotated size, a rotated frame covers
+        // `h` x `w` in the sheet. SubTexture keeps the
+        // unrotated size too, `SubTexture::rect` swaps it.
+        let trim = match (
+            self.trimmed,
+            self.sprite_source_size,
+            self.source_size,
+        ) {
+            (true, Some(sprite_source), Some(source)) => {
+                Some(Trim {
+                    offset: UVec2::new(
+                        sprite_source.x,
+                        sprite_source.y,
+                    ),
+                    source_size: UVec2::new(
+                        source.w, source.h,
+                    ),
+                })
+            }
+            _ => None,
+        };
+        SubTexture {
+            name,
+            x: self.frame.x,
+            y: self.frame.y,
+            width: self.frame.w,
+            height: self.frame.h,
+            rotated: self.rotated,
+            trim,
+            duration: self
+                .duration
+                .map(Duration::from_millis),
+            slice: None,
+        }
+    }
+}
+
+/// Parse either a TexturePacker or an Aseprite JSON
+/// export into sub-textures (in file order) and frame
+/// tags.
+fn parse_sprite_sheet_json(
+    bytes: &[u8],
+) -> Result<
+    (String, Vec<SubTexture>, Vec<FrameTag>),
+    KenneySpriteSheetAssetLoaderError,
+> {
+    let SpriteSheetJson { frames, meta } =
+        serde_json::from_slice(bytes)?;
+
+    let textures = match frames {
+        Frames::Array(frames) => frames
+            .into_iter()
+            .map(|NamedFrame { filename, frame }| {
+                frame.into_sub_texture(filename)
+            })
+            .collect(),
+        Frames::Hash(frames) => frames
+            .into_iter()
+            .map(|(name, value)| {
+                serde_json::from_value::<Frame>(value).map(
+                    |frame| frame.into_sub_texture(name),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    let tags = meta
+        .frame_tags
+        .into_iter()
+        .map(|tag| FrameTag {
+            name: tag.name,
+            from: tag.from,
+            to: tag.to,
+            direction: tag.direction.into(),
+        })
+        .collect();
+
+    Ok((meta.image, textures, tags))
+}
+
+async fn load_sprite_sheet_json(
+    reader: &mut Reader<'_>,
+    load_context: &mut LoadContext<'_>,
+) -> Result<
+    KenneySpriteSheetAsset,
+    KenneySpriteSheetAssetLoaderError,
+> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes).await?;
+
+    let (image, textures, tags) =
+        parse_sprite_sheet_json(&bytes)?;
+
+    // `meta.image` is relative to the json file
+    let image_path = load_context
+        .asset_path()
+        .path()
+        .with_file_name(image);
+
+    finish_sprite_sheet(
+        load_context,
+        image_path,
+        textures,
+        tags,
+    )
+    .await
+}
+
+/// Loads TexturePacker "JSON (Hash)" and "JSON (Array)"
+/// exports saved with a `.tp.json` extension.
+#[derive(Default)]
+pub struct TexturePackerSpriteSheetAssetLoader;
+
+impl AssetLoader for TexturePackerSpriteSheetAssetLoader {
+    type Asset = KenneySpriteSheetAsset;
+    type Settings = ();
+    type Error = KenneySpriteSheetAssetLoaderError;
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>>
+    {
+        Box::pin(async move {
+            load_sprite_sheet_json(reader, load_context)
+                .await
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tp.json"]
+    }
+}
+
+/// Loads Aseprite sheet exports (hash or array) saved
+/// with a `.aseprite.json` extension, including frame
+/// durations and tags.
+#[derive(Default)]
+pub struct AsepriteSpriteSheetAssetLoader;
+
+impl AssetLoader for AsepriteSpriteSheetAssetLoader {
+    type Asset = KenneySpriteSheetAsset;
+    type Settings = ();
+    type Error = KenneySpriteSheetAssetLoaderError;
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>>
+    {
+        Box::pin(async move {
+            load_sprite_sheet_json(reader, load_context)
+                .await
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotated_trimmed_frame() {
+        let json = br#"{
+            "frames": {
+                "ship.png": {
+                    "frame": {"x": 2, "y": 4, "w": 10, "h": 20},
+                    "rotated": true,
+                    "trimmed": true,
+                    "spriteSourceSize": {"x": 1, "y": 3, "w": 10, "h": 20},
+                    "sourceSize": {"w": 12, "h": 24}
+                }
+            },
+            "meta": {"image": "sheet.png"}
+        }"#;
+        let (image, textures, tags) =
+            parse_sprite_sheet_json(json).unwrap();
+        assert_eq!(image, "sheet.png");
+        assert!(tags.is_empty());
+
+        let ship = &textures[0];
+        assert_eq!((ship.width, ship.height), (10, 20));
+        assert_eq!(
+            ship.rect(),
+            Rect::new(2., 4., 22., 14.)
+        );
+        let trim = ship.trim.as_ref().unwrap();
+        assert_eq!(trim.offset, UVec2::new(1, 3));
+        assert_eq!(trim.source_size, UVec2::new(12, 24));
+    }
+
+    #[test]
+    fn aseprite_durations_and_tags() {
+        let json = br#"{
+            "frames": [
+                {
+                    "filename": "fire 0.aseprite",
+                    "frame": {"x": 0, "y": 0, "w": 8, "h": 8},
+                    "duration": 100
+                },
+                {
+                    "filename": "fire 1.aseprite",
+                    "frame": {"x": 8, "y": 0, "w": 8, "h": 8},
+                    "duration": 150
+                }
+            ],
+            "meta": {
+                "image": "fire.png",
+                "frameTags": [
+                    {"name": "burn", "from": 0, "to": 1, "direction": "pingpong"}
+                ]
+            }
+        }"#;
+        let (_, textures, tags) =
+            parse_sprite_sheet_json(json).unwrap();
+
+        assert_eq!(
+            textures[1].duration,
+            Some(Duration::from_millis(150))
+        );
+        assert_eq!(
+            textures[1].rect(),
+            Rect::new(8., 0., 16., 8.)
+        );
+        assert_eq!(tags[0].name, "burn");
+        assert_eq!((tags[0].from, tags[0].to), (0, 1));
+        assert_eq!(
+            tags[0].direction,
+            TagDirection::PingPong
+        );
+    }
+}
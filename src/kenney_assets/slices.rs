@@ -0,0 +1,159 @@
+//! Nine-slice borders for sub-textures.
+//!
+//! Borders either come from `slice*` attributes in the
+//! xml or from a `<sheet>.slices.json` sidecar next to
+//! the sheet image, which is handy for third party
+//! sheets we don't want to edit:
+//!
+//! ```json
+//! {
+//!     "glassPanel.png": 20,
+//!     "buttonBlue.png": { "left": 10, "right": 10, "top": 10, "bottom": 14 }
+//! }
+//! ```
+//!
+//! Sidecar borders take precedence over the xml.
+
+use bevy::{
+    asset::{
+        io::AssetReaderError, LoadContext,
+        ReadAssetBytesError,
+    },
+    prelude::*,
+    utils::HashMap,
+};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use super::{
+    KenneySpriteSheetAssetLoaderError, NineSlice,
+    SubTexture,
+};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SliceBorder {
+    Uniform(f32),
+    Sides {
+        left: f32,
+        right: f32,
+        top: f32,
+        bottom: f32,
+    },
+}
+
+impl From<SliceBorder> for BorderRect {
+    fn from(border: SliceBorder) -> Self {
+        match border {
+            SliceBorder::Uniform(size) => {
+                BorderRect::square(size)
+            }
+            SliceBorder::Sides {
+                left,
+                right,
+                top,
+                bottom,
+            } => BorderRect {
+                left,
+                right,
+                top,
+                bottom,
+            },
+        }
+    }
+}
+
+pub(super) async fn apply_sidecar(
+    load_context: &mut LoadContext<'_>,
+    sidecar_path: PathBuf,
+    textures: &mut [SubTexture],
+) -> Result<(), KenneySpriteSheetAssetLoaderError> {
+    let bytes = match load_context
+        .read_asset_bytes(sidecar_path)
+        .await
+    {
+        Ok(bytes) => bytes,
+        // most sheets don't have a sidecar
+        Err(ReadAssetBytesError::AssetReaderError(
+            AssetReaderError::NotFound(_),
+        )) => return Ok(()),
+        Err(error) => return Err(error.into()),
+    };
+
+    for name in apply_slice_borders(&bytes, textures)? {
+        warn!(
+            "slices sidecar has borders for {name:?}, which isn't in the sheet"
+        );
+    }
+    Ok(())
+}
+
+/// Set the borders in a `.slices.json` sidecar on the
+/// matching `textures`. Returns the sidecar's names that
+/// match no sub-texture, sorted.
+pub fn apply_slice_borders(
+    json: &[u8],
+    textures: &mut [SubTexture],
+) -> Result<Vec<String>, serde_json::Error> {
+    let mut borders: HashMap<String, SliceBorder> =
+        serde_json::from_slice(json)?;
+    for texture in textures.iter_mut() {
+        if let Some(border) = borders.remove(&texture.name)
+        {
+            texture.slice = Some(border.into());
+        }
+    }
+    let mut unmatched: Vec<String> =
+        borders.into_keys().collect();
+    unmatched.sort();
+    Ok(unmatched)
+}
+
+/// Adds the nine pieces of every sliced sub-texture to
+/// the end of `layout`
+pub(super) fn add_nine_slices(
+    textures: &[SubTexture],
+    layout: &mut TextureAtlasLayout,
+) -> Vec<NineSlice> {
+    textures
+        .iter()
+        .enumerate()
+        .filter_map(|(texture_index, texture)| {
+            let border = texture.slice?;
+            if texture.rotated {
+                warn!(
+                    "{:?} is rotated in the sheet and can't be nine-sliced",
+                    texture.name
+                );
+                return None;
+            }
+            let rect = texture.rect();
+            let xs = [
+                rect.min.x,
+                rect.min.x + border.left,
+                rect.max.x - border.right,
+                rect.max.x,
+            ];
+            let ys = [
+                rect.min.y,
+                rect.min.y + border.top,
+                rect.max.y - border.bottom,
+                rect.max.y,
+            ];
+            let pieces = std::array::from_fn(|piece| {
+                let (column, row) = (piece % 3, piece / 3);
+                layout.add_texture(Rect::new(
+                    xs[column],
+                    ys[row],
+                    xs[column + 1],
+                    ys[row + 1],
+                ))
+            });
+            Some(NineSlice {
+                texture_index,
+                border,
+                pieces,
+            })
+        })
+        .collect()
+}
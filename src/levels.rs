@@ -1,5 +1,5 @@
 use bevy::{prelude::*, window::PrimaryWindow};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
     assets::ImageAssets,
@@ -14,6 +14,7 @@ pub struct LevelsPlugin;
 impl Plugin for LevelsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Level>()
+            .init_resource::<RunRng>()
             .add_event::<LevelCompleteEvent>()
             .add_systems(
                 Update,
@@ -27,6 +28,35 @@ impl Plugin for LevelsPlugin {
 #[derive(Resource, Deref, DerefMut, PartialEq, Eq)]
 pub struct Level(usize);
 
+/// All gameplay randomness: where meteors and ufos
+/// spawn, how meteors drift and split. Seeded once per
+/// run so the seed recorded with the run's statistics
+/// reproduces its meteors and ufos.
+#[derive(Resource, Deref, DerefMut)]
+pub struct RunRng {
+    seed: u64,
+    #[deref]
+    rng: StdRng,
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        RunRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for RunRng {
+    fn default() -> Self {
+        RunRng::new(rand::random())
+    }
+}
+
 impl Default for Level {
     fn default() -> Self {
         Self(1)
@@ -63,6 +93,7 @@ fn on_level_complete(
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
     mut events: EventReader<LevelCompleteEvent>,
     mut current_level: ResMut<Level>,
+    mut rng: ResMut<RunRng>,
     window: Query<&Window, With<PrimaryWindow>>,
 ) {
     let Ok(window) = window.get_single() else {
@@ -79,8 +110,6 @@ fn on_level_complete(
         let width = window.resolution.width() / 2.;
         let height = window.resolution.height() / 2.;
 
-        let mut rng = rand::thread_rng();
-
         // TODO: Make sure meteors don't spawn on ships
         for _ in 0..current_level.0 {
             commands.spawn(MeteorBundle::big(
@@ -90,6 +119,7 @@ fn on_level_complete(
                     1.,
                 ),
                 space_sheet,
+                &mut rng,
             ));
         }
     }
@@ -1,24 +1,26 @@
 use assets::ImageAssets;
 use bevy::{prelude::*, window::PrimaryWindow};
-use bevy_xpbd_2d::prelude::*;
-use controls::{Laser, PlayerOwned};
+use collisions::GameLayer;
+use controls::Laser;
 use kenney_assets::KenneySpriteSheetAsset;
-use levels::Level;
-use lives::Lives;
-use meteors::{
-    Meteor, MeteorBundle, MeteorDestroyed, MeteorType,
-};
+use levels::{Level, RunRng};
+use lives::{LifeConfig, Lives};
+use meteors::{MeteorBundle, MeteorType};
 use movement::WrappingMovement;
 use rand::Rng;
 use scores::Scores;
+use settings::GameSettings;
 use ship::{
     PlayerEngineFire, PlayerShipType, ShipBundle,
-    ShipDestroyed,
+    ShipColor, ENGINE_FIRE_ATLAS_INDEX,
 };
-use ufo::{Ufo, UfoDestroyed, UfoOwned};
+use ufo::Ufo;
 use ui::choose_ship::ChooseShipEvent;
 
+pub mod animation;
+pub mod asset_references;
 pub mod assets;
+pub mod collisions;
 pub mod colors;
 pub mod controls;
 pub mod kenney_assets;
@@ -27,8 +29,10 @@ pub mod lives;
 pub mod meteors;
 pub mod movement;
 pub mod scores;
+pub mod screen_shake;
 pub mod settings;
 pub mod ship;
+pub mod stats;
 pub mod ufo;
 pub mod ui;
 
@@ -41,38 +45,54 @@ pub enum GameState {
     Menu,
     ChooseShip,
     Playing,
+    /// the run summary after the last life is lost
+    GameOver,
 }
 
 #[derive(Component)]
 pub struct Player;
 
+#[allow(clippy::too_many_arguments)]
 pub fn reset_game(
     mut commands: Commands,
     mut lives: ResMut<Lives>,
+    life_config: Res<LifeConfig>,
+    settings: Res<GameSettings>,
     entities: Query<
         Entity,
-        Or<(With<MeteorType>, With<Ufo>)>,
+        Or<(
+            With<MeteorType>,
+            With<Ufo>,
+            With<Laser>,
+            With<Player>,
+        )>,
     >,
     mut level: ResMut<Level>,
+    mut rng: ResMut<RunRng>,
     mut scores: ResMut<Scores>,
 ) {
-    lives.0 = 3;
-    *level = Level::default();
     // reset lives count
+    lives.0 = settings
+        .difficulty
+        .starting_lives(life_config.starting_lives);
+    *level = Level::default();
+    *rng = RunRng::default();
     for entity in &entities {
         commands.entity(entity).despawn_recursive();
     }
     if scores.current > scores.high {
         scores.high = scores.current;
-        scores.current = 0;
     }
+    scores.current = 0;
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn start_game(
     mut commands: Commands,
     images: Res<ImageAssets>,
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
     mut player_ship_type_choice: ResMut<PlayerShipType>,
+    mut ship_color_choice: ResMut<ShipColor>,
     // player_ship_type: Res<PlayerShipType>,
     // where the ship should spawn from before landing at
     // 0,0
@@ -81,6 +101,7 @@ pub fn start_game(
         ui::choose_ship::ChooseShipEvent,
     >,
     window: Query<&Window, With<PrimaryWindow>>,
+    mut rng: ResMut<RunRng>,
 ) {
     let Ok(window) = window.get_single() else {
         warn!("no primary window, can't start game");
@@ -89,6 +110,7 @@ pub fn start_game(
 
     let Some(ChooseShipEvent {
         ship_type,
+        ship_color,
         ship_menu_location,
     }) = choose_ship_reader.read().next()
     else {
@@ -96,6 +118,7 @@ pub fn start_game(
         return;
     };
     *player_ship_type_choice = ship_type.clone();
+    *ship_color_choice = *ship_color;
 
     let space_sheet =
         sheets.get(&images.space_sheet).unwrap();
@@ -115,12 +138,13 @@ pub fn start_game(
                 ..default()
             },
             TextureAtlas {
-                index: 74,
+                index: ENGINE_FIRE_ATLAS_INDEX,
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             PlayerEngineFire,
+            PlayerEngineFire::animation(space_sheet),
         ))
         .id();
     commands
@@ -133,14 +157,18 @@ pub fn start_game(
                 ..default()
             },
             texture_atlas: TextureAtlas {
-                index: ship_type.base_atlas_index(),
+                index: ship_type
+                    .atlas_index(*ship_color, space_sheet),
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             player: Player,
             ship_type: ship_type.clone(),
+            ship_color: *ship_color,
             collider: ship_type.collider(),
+            collision_layers: GameLayer::Player
+                .collision_layers(),
             wrapping_movement: WrappingMovement,
         })
         .add_child(engine_fire);
@@ -148,7 +176,6 @@ pub fn start_game(
     let width = window.resolution.width() / 2.;
     let height = window.resolution.height() / 2.;
 
-    let mut rng = rand::thread_rng();
     // TODO: spawn meteors according to current Level
     // TODO: Make sure meteors don't spawn on ships
     commands.spawn(MeteorBundle::big(
@@ -158,140 +185,6 @@ pub fn start_game(
             1.,
         ),
         space_sheet,
+        &mut rng,
     ));
 }
-
-pub fn meteor_laser_collision(
-    mut commands: Commands,
-    mut meteor_destroyed: EventWriter<MeteorDestroyed>,
-    lasers: Query<Entity, With<Laser>>,
-    meteors: Query<
-        (
-            Entity,
-            &CollidingEntities,
-            &MeteorType,
-            &Transform,
-        ),
-        With<Meteor>,
-    >,
-) {
-    for (
-        entity_meteor,
-        colliding_entities,
-        meteor_type,
-        transform,
-    ) in &meteors
-    {
-        if !colliding_entities.is_empty() {
-            for entity_laser in &lasers {
-                if colliding_entities
-                    .contains(&entity_laser)
-                {
-                    commands
-                        .entity(entity_laser)
-                        .despawn_recursive();
-                    commands
-                        .entity(entity_meteor)
-                        .despawn_recursive();
-
-                    meteor_destroyed.send(
-                        MeteorDestroyed {
-                            destroyed_at: *transform,
-                            destroyed_type: *meteor_type,
-                        },
-                    );
-                }
-            }
-        }
-    }
-}
-
-pub fn ufo_laser_collision(
-    mut commands: Commands,
-    mut ufo_destroyed: EventWriter<UfoDestroyed>,
-    lasers: Query<Entity, (With<Laser>, With<PlayerOwned>)>,
-    ufos: Query<
-        (Entity, &CollidingEntities, &Transform),
-        With<Ufo>,
-    >,
-) {
-    for (entity_meteor, colliding_entities, transform) in
-        &ufos
-    {
-        debug_once!("loop");
-        if !colliding_entities.is_empty() {
-            for entity_laser in &lasers {
-                if colliding_entities
-                    .contains(&entity_laser)
-                {
-                    debug!("hit");
-                    commands
-                        .entity(entity_laser)
-                        .despawn_recursive();
-                    commands
-                        .entity(entity_meteor)
-                        .despawn_recursive();
-
-                    ufo_destroyed.send(UfoDestroyed {
-                        destroyed_at: *transform,
-                    });
-                }
-            }
-        }
-    }
-}
-
-pub fn ship_meteor_collision(
-    mut commands: Commands,
-    mut ship_destroyed: EventWriter<ShipDestroyed>,
-    meteors: Query<
-        Entity,
-        Or<(With<Meteor>, With<Ufo>, With<UfoOwned>)>,
-    >,
-    query_lasers: Query<
-        Entity,
-        (With<UfoOwned>, With<Laser>),
-    >,
-    player_ship: Query<
-        (
-            Entity,
-            &CollidingEntities,
-            &Transform,
-            &PlayerShipType,
-        ),
-        With<Player>,
-    >,
-) {
-    for (
-        entity_player,
-        colliding_entities,
-        transform,
-        ship_type,
-    ) in &player_ship
-    {
-        if !colliding_entities.is_empty() {
-            for entity_meteor in &meteors {
-                if colliding_entities
-                    .contains(&entity_meteor)
-                {
-                    commands
-                        .entity(entity_player)
-                        .despawn_recursive();
-
-                    if let Ok(laser_entity) =
-                        query_lasers.get(entity_meteor)
-                    {
-                        commands
-                            .entity(laser_entity)
-                            .despawn_recursive();
-                    };
-
-                    ship_destroyed.send(ShipDestroyed {
-                        destroyed_at: *transform,
-                        ship_type: ship_type.clone(),
-                    });
-                }
-            }
-        }
-    }
-}
@@ -3,23 +3,50 @@ use bevy::prelude::*;
 use crate::{
     assets::ImageAssets,
     kenney_assets::KenneySpriteSheetAsset,
-    ship::PlayerShipType, GameState, Player,
+    scores::{ScoreUpdate, Scores},
+    ship::{PlayerShipType, ShipColor},
+    ui::pause::Pausable,
+    GameState, Player,
 };
 
 pub struct LifePlugin;
 
 impl Plugin for LifePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Lives(3))
+        let config = LifeConfig::default();
+        app.insert_resource(Lives(config.starting_lives))
+            .insert_resource(config)
+            .init_resource::<ExtraLivesAwarded>()
             .add_event::<RemoveLifeEvent>()
+            .add_event::<AddLifeEvent>()
+            .add_event::<ExtraLifeGained>()
             .add_systems(
                 Update,
-                (lives, render_lives)
+                (
+                    award_extra_lives.after(ScoreUpdate),
+                    lives.in_set(LivesUpdate),
+                    render_lives,
+                )
+                    .chain()
                     .run_if(in_state(GameState::Playing)),
             )
+            .add_systems(
+                Update,
+                (
+                    show_extra_life_notification
+                        .after(LivesUpdate),
+                    fade_extra_life_notification,
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(
+                        resource_equals(
+                            Pausable::NotPaused,
+                        ),
+                    ),
+            )
             .add_systems(
                 OnEnter(GameState::Playing),
-                spawn_life_ui,
+                (spawn_life_ui, reset_extra_lives),
             )
             .add_systems(
                 OnExit(GameState::Playing),
@@ -31,24 +58,121 @@ impl Plugin for LifePlugin {
 #[derive(Debug, Resource, PartialEq, Eq)]
 pub struct Lives(pub usize);
 
+/// The system that adds and removes [`Lives`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LivesUpdate;
+
+/// How many lives a player starts with, can earn, and
+/// when they earn them
+#[derive(Debug, Resource, Clone)]
+pub struct LifeConfig {
+    pub starting_lives: usize,
+    /// extra lives are not awarded beyond this
+    pub max_lives: usize,
+    pub extra_lives: ExtraLifeThresholds,
+}
+
+impl Default for LifeConfig {
+    fn default() -> Self {
+        LifeConfig {
+            starting_lives: 3,
+            max_lives: 5,
+            extra_lives: ExtraLifeThresholds::Every(10_000),
+        }
+    }
+}
+
+/// The scores at which an extra life is earned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtraLifeThresholds {
+    /// every time the score passes a multiple of this
+    Every(usize),
+    /// once at each of these scores
+    At(Vec<usize>),
+    Never,
+}
+
+impl ExtraLifeThresholds {
+    /// How many thresholds `score` has passed
+    pub fn reached(&self, score: usize) -> usize {
+        match self {
+            ExtraLifeThresholds::Every(0) => 0,
+            ExtraLifeThresholds::Every(points) => {
+                score / points
+            }
+            ExtraLifeThresholds::At(scores) => scores
+                .iter()
+                .filter(|threshold| score >= **threshold)
+                .count(),
+            ExtraLifeThresholds::Never => 0,
+        }
+    }
+}
+
+/// How many extra life thresholds have already been
+/// handed out this game. Thresholds reached while at
+/// [`LifeConfig::max_lives`] are used up too.
+#[derive(Debug, Resource, Default)]
+struct ExtraLivesAwarded(usize);
+
 #[derive(Event)]
 pub struct RemoveLifeEvent;
 
+#[derive(Event)]
+pub struct AddLifeEvent;
+
+/// An [`AddLifeEvent`] that added a life, rather than
+/// being used up at [`LifeConfig::max_lives`]
+#[derive(Event)]
+pub struct ExtraLifeGained;
+
+fn reset_extra_lives(
+    mut awarded: ResMut<ExtraLivesAwarded>,
+) {
+    awarded.0 = 0;
+}
+
+fn award_extra_lives(
+    scores: Res<Scores>,
+    config: Res<LifeConfig>,
+    mut awarded: ResMut<ExtraLivesAwarded>,
+    mut life_events: EventWriter<AddLifeEvent>,
+) {
+    if !scores.is_changed() {
+        return;
+    }
+    let reached =
+        config.extra_lives.reached(scores.current);
+    for _ in awarded.0..reached {
+        life_events.send(AddLifeEvent);
+    }
+    awarded.0 = awarded.0.max(reached);
+}
+
 fn lives(
     mut life_events: EventReader<RemoveLifeEvent>,
+    mut extra_life_events: EventReader<AddLifeEvent>,
     mut lives: ResMut<Lives>,
+    config: Res<LifeConfig>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut gained: EventWriter<ExtraLifeGained>,
 ) {
+    for _event in extra_life_events.read() {
+        if lives.0 < config.max_lives {
+            lives.0 += 1;
+            gained.send(ExtraLifeGained);
+        }
+    }
     for _event in life_events.read() {
         match lives.0.checked_sub(1) {
             Some(new_lives) => {
                 lives.0 = new_lives;
                 if lives.0 == 0 {
-                    next_state.set(GameState::Menu);
+                    next_state.set(GameState::GameOver);
                 }
             }
             None => {
-                next_state.set(GameState::Menu);
+                next_state.set(GameState::GameOver);
             }
         }
     }
@@ -76,9 +200,16 @@ fn spawn_life_ui(mut commands: Commands) {
         LifeContainer,
     ));
 }
+#[allow(clippy::type_complexity)]
 fn remove_life_ui(
     mut commands: Commands,
-    query: Query<Entity, With<LifeContainer>>,
+    query: Query<
+        Entity,
+        Or<(
+            With<LifeContainer>,
+            With<ExtraLifeNotification>,
+        )>,
+    >,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
@@ -90,10 +221,15 @@ fn render_lives(
     lives: Res<Lives>,
     life_container: Query<Entity, With<LifeContainer>>,
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
-    player_query: Query<&PlayerShipType, With<Player>>,
+    player_query: Query<
+        (&PlayerShipType, &ShipColor),
+        With<Player>,
+    >,
     life_sprite_query: Query<(Entity, &LifeIndex)>,
 ) {
-    let Ok(ship_type) = player_query.get_single() else {
+    let Ok((ship_type, ship_color)) =
+        player_query.get_single()
+    else {
         if player_query.iter().count() > 1 {
             error_once!(
             "Only expected one PlayerShipType component. got {}",
@@ -127,7 +263,10 @@ fn render_lives(
                         ..default()
                     },
                     TextureAtlas {
-                        index: ship_type.life_atlas_index(),
+                        index: ship_type.life_atlas_index(
+                            *ship_color,
+                            space_sheet,
+                        ),
                         layout: space_sheet
                             .texture_atlas_layout
                             .clone(),
@@ -148,3 +287,114 @@ fn render_lives(
         }
     }
 }
+
+#[derive(Component)]
+struct ExtraLifeNotification(Timer);
+
+fn show_extra_life_notification(
+    mut commands: Commands,
+    mut events: EventReader<ExtraLifeGained>,
+    existing: Query<Entity, With<ExtraLifeNotification>>,
+) {
+    if events.is_empty() {
+        return;
+    }
+    events.clear();
+
+    for entity in &existing {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(25.),
+                    width: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            ExtraLifeNotification(Timer::from_seconds(
+                2.,
+                TimerMode::Once,
+            )),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Extra Life!",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn fade_extra_life_notification(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut notifications: Query<(
+        Entity,
+        &mut ExtraLifeNotification,
+        &Children,
+    )>,
+    mut texts: Query<&mut Text>,
+) {
+    for (entity, mut notification, children) in
+        &mut notifications
+    {
+        notification.0.tick(time.delta());
+        if notification.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = notification.0.fraction_remaining();
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                for section in &mut text.sections {
+                    section.style.color.set_a(alpha);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_counts_each_multiple_passed() {
+        let every = ExtraLifeThresholds::Every(10_000);
+        assert_eq!(every.reached(9_999), 0);
+        assert_eq!(every.reached(10_000), 1);
+        // one big score jumping several thresholds
+        assert_eq!(every.reached(35_000), 3);
+    }
+
+    #[test]
+    fn at_counts_each_score_passed() {
+        let at =
+            ExtraLifeThresholds::At(vec![5_000, 20_000]);
+        assert_eq!(at.reached(4_999), 0);
+        assert_eq!(at.reached(5_000), 1);
+        assert_eq!(at.reached(19_999), 1);
+        assert_eq!(at.reached(20_000), 2);
+        assert_eq!(at.reached(1_000_000), 2);
+    }
+
+    #[test]
+    fn never_and_zero_award_nothing() {
+        assert_eq!(
+            ExtraLifeThresholds::Never.reached(50_000),
+            0
+        );
+        assert_eq!(
+            ExtraLifeThresholds::Every(0).reached(50_000),
+            0
+        );
+    }
+}
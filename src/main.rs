@@ -8,12 +8,12 @@ use bevy::{
 use bevy_hanabi::prelude::*;
 use bevy_xpbd_2d::prelude::*;
 use space_shooter::{
-    assets::AssetsPlugin, controls::ControlsPlugin,
-    levels::LevelsPlugin, lives::LifePlugin,
-    meteor_laser_collision, reset_game,
-    scores::ScorePlugin, settings::SettingsPlugin,
-    ship::ShipPlugin, ship_meteor_collision,
-    ufo::UfoPlugin, ufo_laser_collision, ui::UiPlugin,
+    animation::SpriteAnimationPlugin, assets::AssetsPlugin,
+    collisions::CollisionsPlugin, controls::ControlsPlugin,
+    levels::LevelsPlugin, lives::LifePlugin, reset_game,
+    scores::ScorePlugin, screen_shake::ScreenShakePlugin,
+    settings::SettingsPlugin, ship::ShipPlugin,
+    stats::StatsPlugin, ufo::UfoPlugin, ui::UiPlugin,
     GameState,
 };
 use space_shooter::{
@@ -21,17 +21,15 @@ use space_shooter::{
     movement::MovementPlugin,
     start_game,
     ui::{
-        choose_ship::ChooseShipPlugin,
-        pause::{Pausable, PausePlugin},
+        choose_ship::ChooseShipPlugin, pause::PausePlugin,
     },
 };
 
 fn main() {
     let mut wgpu_settings = WgpuSettings::default();
-    wgpu_settings.features.set(
-        WgpuFeatures::VERTEX_WRITABLE_STORAGE,
-        true,
-    );
+    wgpu_settings
+        .features
+        .set(WgpuFeatures::VERTEX_WRITABLE_STORAGE, true);
 
     App::new()
         .insert_resource(ClearColor(Color::rgb(
@@ -54,9 +52,12 @@ fn main() {
             PhysicsPlugins::default(),
             PhysicsDebugPlugin::default(),
             HanabiPlugin,
+            CollisionsPlugin,
+            ScreenShakePlugin,
             (
                 SettingsPlugin,
                 ControlsPlugin,
+                SpriteAnimationPlugin,
                 AssetsPlugin,
                 UiPlugin,
                 MeteorPlugin,
@@ -68,6 +69,7 @@ fn main() {
                 LevelsPlugin,
                 ScorePlugin,
                 UfoPlugin,
+                StatsPlugin,
             ),
         ))
         .init_state::<GameState>()
@@ -76,18 +78,6 @@ fn main() {
             OnEnter(GameState::Playing),
             (reset_game, start_game).chain(),
         )
-        .add_systems(
-            Update,
-            (
-                meteor_laser_collision,
-                ship_meteor_collision,
-                ufo_laser_collision,
-            )
-                .run_if(in_state(GameState::Playing))
-                .run_if(resource_equals(
-                    Pausable::NotPaused,
-                )),
-        )
         .run();
 }
 
@@ -2,8 +2,12 @@ use std::f32::consts::TAU;
 
 use crate::{
     assets::ImageAssets,
+    collisions::GameLayer,
+    controls::Shooter,
     kenney_assets::KenneySpriteSheetAsset,
+    levels::RunRng,
     movement::{LinearMovement, Spin, WrappingMovement},
+    settings::GameSettings,
     ui::pause::Pausable,
     GameState,
 };
@@ -17,6 +21,7 @@ pub struct MeteorPlugin;
 impl Plugin for MeteorPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, register_meteor_effect)
+            .add_systems(Update, apply_particle_density)
             .add_systems(
                 PostUpdate,
                 sandbox_meteor_destroyed_event_handler
@@ -33,7 +38,7 @@ fn register_meteor_effect(
     mut commands: Commands,
     mut effects: ResMut<Assets<EffectAsset>>,
 ) {
-    let spawner = Spawner::once(100.0.into(), false);
+    let spawner = explosion_spawner(1.0);
 
     let writer = ExprWriter::new();
 
@@ -96,11 +101,42 @@ fn register_meteor_effect(
         .insert(Name::new("effect:meteor_explosion"));
 }
 
+/// Particles in an explosion at full particle density
+const EXPLOSION_PARTICLES: f32 = 100.0;
+
+fn explosion_spawner(density: f32) -> Spawner {
+    Spawner::once(
+        (EXPLOSION_PARTICLES * density).into(),
+        false,
+    )
+}
+
+/// Resize the explosions to the particle density
+/// setting. Hanabi only reads the spawner when it
+/// creates the [`EffectSpawner`], so that is removed to
+/// have it recreated.
+fn apply_particle_density(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut effects: Query<(Entity, &mut ParticleEffect)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (entity, mut effect) in &mut effects {
+        effect.spawner = Some(explosion_spawner(
+            settings.particle_density,
+        ));
+        commands.entity(entity).remove::<EffectSpawner>();
+    }
+}
+
 #[derive(Bundle)]
 pub struct MeteorBundle {
     meteor_type: MeteorType,
     meteor: Meteor,
     collider: Collider,
+    collision_layers: CollisionLayers,
     sprite_bundle: SpriteBundle,
     texture_atlas: TextureAtlas,
     linear_movement: LinearMovement,
@@ -116,6 +152,13 @@ pub enum MeteorType {
 #[derive(Component)]
 pub struct Meteor;
 
+/// `meteorGrey_big1.png`
+pub const METEOR_BIG_ATLAS_INDEX: usize = 163;
+/// `meteorGrey_med1.png`
+pub const METEOR_MEDIUM_ATLAS_INDEX: usize = 167;
+/// `meteorGrey_small1.png`
+pub const METEOR_SMALL_ATLAS_INDEX: usize = 169;
+
 const METEOR_BASE_SPEED_BIG: f32 = 1.;
 const METEOR_BASE_SPEED_MEDIUM: f32 = 1.2;
 const METEOR_BASE_SPEED_SMALL: f32 = 1.4;
@@ -124,8 +167,8 @@ impl MeteorBundle {
     pub fn big(
         transform: Transform,
         space_sheet: &KenneySpriteSheetAsset,
+        rng: &mut RunRng,
     ) -> MeteorBundle {
-        let mut rng = rand::thread_rng();
         let x = rng.gen::<f32>() * METEOR_BASE_SPEED_BIG;
         let y = rng.gen::<f32>() * METEOR_BASE_SPEED_BIG;
         let rotation = rng.gen::<f32>() * TAU;
@@ -134,21 +177,21 @@ impl MeteorBundle {
             meteor_type: MeteorType::Big,
             meteor: Meteor,
             collider: Collider::circle(42.),
+            collision_layers: GameLayer::Meteor
+                .collision_layers(),
             sprite_bundle: SpriteBundle {
                 transform,
                 texture: space_sheet.sheet.clone(),
                 ..default()
             },
             texture_atlas: TextureAtlas {
-                index: 163,
+                index: METEOR_BIG_ATLAS_INDEX,
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             linear_movement: LinearMovement {
-                movement_factor: Vec2::new(
-                    x as f32, y as f32,
-                ),
+                movement_factor: Vec2::new(x, y),
                 movement_direction: Quat::from_rotation_z(
                     rotation,
                 ),
@@ -160,8 +203,8 @@ impl MeteorBundle {
     pub fn medium(
         transform: Transform,
         space_sheet: &KenneySpriteSheetAsset,
+        rng: &mut RunRng,
     ) -> MeteorBundle {
-        let mut rng = rand::thread_rng();
         let x = rng.gen::<f32>() * METEOR_BASE_SPEED_MEDIUM;
         let y = rng.gen::<f32>() * METEOR_BASE_SPEED_MEDIUM;
         let rotation = rng.gen::<f32>() * TAU;
@@ -170,21 +213,21 @@ impl MeteorBundle {
             meteor_type: MeteorType::Medium,
             meteor: Meteor,
             collider: Collider::circle(21.),
+            collision_layers: GameLayer::Meteor
+                .collision_layers(),
             sprite_bundle: SpriteBundle {
                 transform,
                 texture: space_sheet.sheet.clone(),
                 ..default()
             },
             texture_atlas: TextureAtlas {
-                index: 167,
+                index: METEOR_MEDIUM_ATLAS_INDEX,
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             linear_movement: LinearMovement {
-                movement_factor: Vec2::new(
-                    x as f32, y as f32,
-                ),
+                movement_factor: Vec2::new(x, y),
                 movement_direction: Quat::from_rotation_z(
                     rotation,
                 ),
@@ -196,8 +239,8 @@ impl MeteorBundle {
     pub fn small(
         transform: Transform,
         space_sheet: &KenneySpriteSheetAsset,
+        rng: &mut RunRng,
     ) -> MeteorBundle {
-        let mut rng = rand::thread_rng();
         let x = rng.gen::<f32>() * METEOR_BASE_SPEED_SMALL;
         let y = rng.gen::<f32>() * METEOR_BASE_SPEED_SMALL;
         let rotation = rng.gen::<f32>() * TAU;
@@ -206,21 +249,21 @@ impl MeteorBundle {
             meteor_type: MeteorType::Small,
             meteor: Meteor,
             collider: Collider::circle(14.),
+            collision_layers: GameLayer::Meteor
+                .collision_layers(),
             sprite_bundle: SpriteBundle {
                 transform,
                 texture: space_sheet.sheet.clone(),
                 ..default()
             },
             texture_atlas: TextureAtlas {
-                index: 169,
+                index: METEOR_SMALL_ATLAS_INDEX,
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             linear_movement: LinearMovement {
-                movement_factor: Vec2::new(
-                    x as f32, y as f32,
-                ),
+                movement_factor: Vec2::new(x, y),
                 movement_direction: Quat::from_rotation_z(
                     rotation,
                 ),
@@ -235,6 +278,7 @@ impl MeteorBundle {
 pub struct MeteorDestroyed {
     pub destroyed_at: Transform,
     pub destroyed_type: MeteorType,
+    pub destroyed_by: Shooter,
 }
 
 fn sandbox_meteor_destroyed_event_handler(
@@ -248,6 +292,7 @@ fn sandbox_meteor_destroyed_event_handler(
         &mut EffectSpawner,
         &mut Transform,
     )>,
+    mut rng: ResMut<RunRng>,
 ) {
     let Some(space_sheet) = sheets.get(&images.space_sheet)
     else {
@@ -255,7 +300,6 @@ fn sandbox_meteor_destroyed_event_handler(
         return;
     };
 
-    let mut rng = rand::thread_rng();
     // Note: On first frame where the effect spawns,
     // EffectSpawner is spawned during PostUpdate,
     // so will not be available yet. Ignore for a
@@ -273,6 +317,7 @@ fn sandbox_meteor_destroyed_event_handler(
     for MeteorDestroyed {
         destroyed_at,
         destroyed_type,
+        ..
     } in &mut events.read()
     {
         effect_transform.translation =
@@ -306,6 +351,7 @@ fn sandbox_meteor_destroyed_event_handler(
                             1.,
                         ),
                         space_sheet,
+                        &mut rng,
                     ));
                 }
             }
@@ -323,6 +369,7 @@ fn sandbox_meteor_destroyed_event_handler(
                             1.,
                         ),
                         space_sheet,
+                        &mut rng,
                     ));
                 }
             }
@@ -1,7 +1,14 @@
 use bevy::prelude::*;
+use std::time::Duration;
 
 use crate::{
-    meteors::MeteorDestroyed, ufo::UfoDestroyed, GameState,
+    controls::{LaserMissed, PlayerId},
+    meteors::MeteorDestroyed,
+    settings::GameSettings,
+    ship::ShipDestroyed,
+    ufo::UfoDestroyed,
+    ui::pause::Pausable,
+    GameState,
 };
 
 pub struct ScorePlugin;
@@ -9,14 +16,40 @@ pub struct ScorePlugin;
 impl Plugin for ScorePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Scores>()
+            .init_resource::<ComboConfig>()
+            .init_resource::<Combo>()
+            .add_event::<PointsScored>()
             .add_systems(
                 Update,
-                (score_ufos, score_meteors, render_score)
+                (
+                    (break_combo, tick_combo)
+                        .before(ScoreUpdate)
+                        .run_if(resource_equals(
+                            Pausable::NotPaused,
+                        )),
+                    (score_ufos, score_meteors)
+                        .in_set(ScoreUpdate),
+                    (render_score, render_combo)
+                        .after(ScoreUpdate),
+                )
                     .run_if(in_state(GameState::Playing)),
             )
+            .add_systems(
+                Update,
+                (
+                    spawn_score_popups.after(ScoreUpdate),
+                    animate_score_popups,
+                )
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(
+                        resource_equals(
+                            Pausable::NotPaused,
+                        ),
+                    ),
+            )
             .add_systems(
                 OnEnter(GameState::Playing),
-                spawn_scores_ui,
+                (spawn_scores_ui, reset_combo),
             )
             .add_systems(
                 OnExit(GameState::Playing),
@@ -25,33 +58,160 @@ impl Plugin for ScorePlugin {
     }
 }
 
+/// The systems that add points to [`Scores`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScoreUpdate;
+
 #[derive(Resource, PartialEq, Eq, Debug, Default)]
 pub struct Scores {
     pub current: usize,
     pub high: usize,
 }
 
+/// Points added to the score, and where they were
+/// earned
+#[derive(Debug, Event)]
+pub struct PointsScored {
+    pub player: PlayerId,
+    /// points after the multiplier was applied
+    pub points: usize,
+    pub multiplier: usize,
+    pub at: Vec3,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ComboConfig {
+    /// how long after a hit the next one still counts
+    /// towards the combo
+    pub window: Duration,
+    pub max_multiplier: usize,
+}
+
+impl Default for ComboConfig {
+    fn default() -> Self {
+        ComboConfig {
+            window: Duration::from_secs(2),
+            max_multiplier: 8,
+        }
+    }
+}
+
+/// Consecutive hits within [`ComboConfig::window`] of
+/// each other raise the score multiplier. Missed shots,
+/// deaths and waiting too long reset it.
+#[derive(Resource, Debug, Default)]
+pub struct Combo {
+    hits: usize,
+    multiplier: usize,
+    timer: Timer,
+}
+
+impl Combo {
+    /// The multiplier points are currently scored with
+    pub fn multiplier(&self) -> usize {
+        self.multiplier.max(1)
+    }
+    /// How much of the combo window is left, from 1 to 0
+    pub fn time_remaining(&self) -> f32 {
+        if self.hits == 0 {
+            0.
+        } else {
+            self.timer.fraction_remaining()
+        }
+    }
+    /// Register a hit and return the multiplier it
+    /// scores with
+    fn hit(&mut self, config: &ComboConfig) -> usize {
+        self.hits += 1;
+        self.multiplier =
+            self.hits.min(config.max_multiplier);
+        self.timer =
+            Timer::new(config.window, TimerMode::Once);
+        self.multiplier()
+    }
+    fn reset(&mut self) {
+        self.hits = 0;
+        self.multiplier = 1;
+    }
+}
+
+fn reset_combo(mut combo: ResMut<Combo>) {
+    combo.reset();
+}
+
+fn tick_combo(time: Res<Time>, mut combo: ResMut<Combo>) {
+    if combo.hits == 0 {
+        return;
+    }
+    combo.timer.tick(time.delta());
+    if combo.timer.finished() {
+        combo.reset();
+    }
+}
+
+fn break_combo(
+    mut missed: EventReader<LaserMissed>,
+    mut ship_destroyed: EventReader<ShipDestroyed>,
+    mut combo: ResMut<Combo>,
+) {
+    let missed = missed.read().count() > 0;
+    let died = ship_destroyed.read().count() > 0;
+    if (missed || died) && combo.hits > 0 {
+        combo.reset();
+    }
+}
+
 fn score_meteors(
     mut scores: ResMut<Scores>,
+    mut combo: ResMut<Combo>,
+    config: Res<ComboConfig>,
     mut reader: EventReader<MeteorDestroyed>,
+    mut points_scored: EventWriter<PointsScored>,
 ) {
     for meteor in reader.read() {
+        // meteors shot down by ufos aren't worth anything
+        let Some(player) = meteor.destroyed_by.player()
+        else {
+            continue;
+        };
         let meteor_score_value = match meteor.destroyed_type
         {
             crate::meteors::MeteorType::Big => 20,
             crate::meteors::MeteorType::Medium => 60,
             crate::meteors::MeteorType::Small => 100,
         };
-        scores.current += meteor_score_value;
+        let multiplier = combo.hit(&config);
+        let points = meteor_score_value * multiplier;
+        scores.current += points;
+        points_scored.send(PointsScored {
+            player,
+            points,
+            multiplier,
+            at: meteor.destroyed_at.translation,
+        });
     }
 }
 
 fn score_ufos(
     mut scores: ResMut<Scores>,
+    mut combo: ResMut<Combo>,
+    config: Res<ComboConfig>,
     mut reader: EventReader<UfoDestroyed>,
+    mut points_scored: EventWriter<PointsScored>,
 ) {
-    for _ufo in reader.read() {
-        scores.current += 500;
+    for ufo in reader.read() {
+        let Some(player) = ufo.destroyed_by.player() else {
+            continue;
+        };
+        let multiplier = combo.hit(&config);
+        let points = 500 * multiplier;
+        scores.current += points;
+        points_scored.send(PointsScored {
+            player,
+            points,
+            multiplier,
+            at: ufo.destroyed_at.translation,
+        });
     }
 }
 
@@ -61,6 +221,17 @@ struct ScoreContainer;
 #[derive(Component)]
 struct ScoreDisplay;
 
+/// Holds the multiplier and timer bar, only shown while
+/// a combo is going
+#[derive(Component)]
+struct ComboDisplay;
+
+#[derive(Component)]
+struct ComboMultiplierText;
+
+#[derive(Component)]
+struct ComboTimerBar;
+
 // when we start playing
 fn spawn_scores_ui(mut commands: Commands) {
     let id = commands
@@ -97,12 +268,84 @@ fn spawn_scores_ui(mut commands: Commands) {
             },
             ScoreContainer,
         ))
-        .add_child(id);
+        .add_child(id)
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction:
+                                FlexDirection::Column,
+                            justify_content:
+                                JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::left(Val::Px(
+                                20.,
+                            )),
+                            row_gap: Val::Px(4.),
+                            ..default()
+                        },
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    ComboDisplay,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "x1",
+                            TextStyle {
+                                font_size: 30.0,
+                                color: Color::GOLD,
+                                ..default()
+                            },
+                        ),
+                        ComboMultiplierText,
+                    ));
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                width: Val::Px(80.),
+                                height: Val::Px(6.),
+                                ..default()
+                            },
+                            background_color: Color::rgba(
+                                1., 1., 1., 0.2,
+                            )
+                            .into(),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        width: Val::Percent(
+                                            100.,
+                                        ),
+                                        height:
+                                            Val::Percent(
+                                                100.,
+                                            ),
+                                        ..default()
+                                    },
+                                    background_color:
+                                        Color::GOLD.into(),
+                                    ..default()
+                                },
+                                ComboTimerBar,
+                            ));
+                        });
+                });
+        });
 }
 
+#[allow(clippy::type_complexity)]
 fn remove_scores_ui(
     mut commands: Commands,
-    query: Query<Entity, With<ScoreContainer>>,
+    query: Query<
+        Entity,
+        Or<(With<ScoreContainer>, With<ScorePopup>)>,
+    >,
 ) {
     for entity in &query {
         commands.entity(entity).despawn_recursive();
@@ -122,3 +365,117 @@ fn render_score(
         section.value = scores.current.to_string();
     }
 }
+
+fn render_combo(
+    combo: Res<Combo>,
+    mut display: Query<&mut Visibility, With<ComboDisplay>>,
+    mut text: Query<&mut Text, With<ComboMultiplierText>>,
+    mut bar: Query<&mut Style, With<ComboTimerBar>>,
+) {
+    let active = combo.multiplier() > 1;
+    for mut visibility in &mut display {
+        *visibility = if active {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    if !active {
+        return;
+    }
+    for mut text in &mut text {
+        text.sections[0].value =
+            format!("x{}", combo.multiplier());
+    }
+    for mut style in &mut bar {
+        style.width =
+            Val::Percent(combo.time_remaining() * 100.);
+    }
+}
+
+/// World-space text showing the points earned at the
+/// spot they were earned
+#[derive(Component)]
+struct ScorePopup(Timer);
+
+/// how fast popups float upwards, in pixels per second
+const SCORE_POPUP_RISE_SPEED: f32 = 40.;
+
+fn spawn_score_popups(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    mut events: EventReader<PointsScored>,
+) {
+    if !settings.score_popups {
+        events.clear();
+        return;
+    }
+    for PointsScored {
+        points,
+        multiplier,
+        at,
+        ..
+    } in events.read()
+    {
+        let mut sections = vec![TextSection::new(
+            points.to_string(),
+            TextStyle {
+                font_size: 24.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )];
+        if *multiplier > 1 {
+            sections.push(TextSection::new(
+                format!(" x{multiplier}"),
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::GOLD,
+                    ..default()
+                },
+            ));
+        }
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_sections(sections)
+                    .with_justify(JustifyText::Center),
+                // above meteors and ships
+                transform: Transform::from_xyz(
+                    at.x, at.y, 5.,
+                ),
+                ..default()
+            },
+            ScorePopup(Timer::from_seconds(
+                1.,
+                TimerMode::Once,
+            )),
+        ));
+    }
+}
+
+fn animate_score_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popups: Query<(
+        Entity,
+        &mut ScorePopup,
+        &mut Transform,
+        &mut Text,
+    )>,
+) {
+    for (entity, mut popup, mut transform, mut text) in
+        &mut popups
+    {
+        popup.0.tick(time.delta());
+        if popup.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation.y +=
+            SCORE_POPUP_RISE_SPEED * time.delta_seconds();
+        let alpha = popup.0.fraction_remaining();
+        for section in &mut text.sections {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Shakes the camera when things are destroyed, scaled
+//! by the screen shake setting.
+//!
+//! Destruction adds [`Trauma`], which wears off over
+//! time. The shake grows with the square of the trauma,
+//! so small hits barely move the camera while losing
+//! the ship is felt.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    meteors::{MeteorDestroyed, MeteorType},
+    settings::GameSettings,
+    ship::ShipDestroyed,
+    ufo::UfoDestroyed,
+    ui::pause::Pausable,
+    GameState,
+};
+
+pub struct ScreenShakePlugin;
+
+impl Plugin for ScreenShakePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Trauma>()
+            .add_systems(
+                Update,
+                (add_trauma, shake_camera)
+                    .chain()
+                    .run_if(in_state(GameState::Playing))
+                    .run_if(resource_equals(
+                        Pausable::NotPaused,
+                    )),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                reset_camera,
+            );
+    }
+}
+
+/// How far the camera is thrown at full trauma, in
+/// pixels
+const MAX_OFFSET: f32 = 16.0;
+/// Trauma lost per second
+const TRAUMA_DECAY: f32 = 1.5;
+
+/// From 0 (still) to 1 (shaking as hard as it can)
+#[derive(Resource, Debug, Default)]
+pub struct Trauma(pub f32);
+
+fn add_trauma(
+    mut trauma: ResMut<Trauma>,
+    mut meteors: EventReader<MeteorDestroyed>,
+    mut ufos: EventReader<UfoDestroyed>,
+    mut ships: EventReader<ShipDestroyed>,
+) {
+    let added = meteors
+        .read()
+        .map(|meteor| match meteor.destroyed_type {
+            MeteorType::Big => 0.3,
+            MeteorType::Medium => 0.2,
+            MeteorType::Small => 0.1,
+        })
+        .chain(ufos.read().map(|_| 0.4))
+        .chain(ships.read().map(|_| 0.8))
+        .sum::<f32>();
+    if added > 0. {
+        trauma.0 = (trauma.0 + added).min(1.);
+    }
+}
+
+fn shake_camera(
+    time: Res<Time>,
+    settings: Res<GameSettings>,
+    mut trauma: ResMut<Trauma>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    if trauma.0 <= 0. {
+        return;
+    }
+    trauma.0 = (trauma.0
+        - TRAUMA_DECAY * time.delta_seconds())
+    .max(0.);
+    let shake = trauma.0
+        * trauma.0
+        * settings.effective_screen_shake();
+
+    // cosmetic, so it doesn't use the run's seeded rng
+    let mut rng = rand::thread_rng();
+    let offset = Vec2::new(
+        rng.gen_range(-1.0..1.0),
+        rng.gen_range(-1.0..1.0),
+    ) * MAX_OFFSET
+        * shake;
+    for mut transform in &mut cameras {
+        transform.translation.x = offset.x;
+        transform.translation.y = offset.y;
+    }
+}
+
+fn reset_camera(
+    mut trauma: ResMut<Trauma>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    trauma.0 = 0.;
+    for mut transform in &mut cameras {
+        transform.translation.x = 0.;
+        transform.translation.y = 0.;
+    }
+}
@@ -1,28 +1,429 @@
-use bevy::prelude::*;
+//! Player settings, edited on the settings page.
+//!
+//! Settings are loaded from `settings.json` in the
+//! working directory at startup and saved back shortly
+//! after they change. Audio, display and ui scale
+//! changes are applied to the running game as soon as
+//! they're made.
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{
+        PresentMode, PrimaryWindow, WindowMode,
+        WindowResized,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+use crate::ship::{PlayerShipType, ShipColor};
 
 pub struct SettingsPlugin;
 
 impl Plugin for SettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<GameSettings>();
+        app.insert_resource(load_settings()).add_systems(
+            Update,
+            (
+                apply_audio_settings,
+                (track_window_size, apply_display_settings)
+                    .chain(),
+                apply_ui_scale,
+                save_settings,
+            ),
+        );
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+const SETTINGS_PATH: &str = "settings.json";
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
 pub enum AudioSettings {
     ON,
     OFF,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Resource)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Resource,
+    Serialize,
+    Deserialize,
+)]
+#[serde(default)]
 pub struct GameSettings {
     pub audio: AudioSettings,
+    /// scales every sound, from 0 to 1
+    pub master_volume: f32,
+    /// scales the sound effects, from 0 to 1
+    pub effects_volume: f32,
+    pub controls: KeyBindings,
+    pub display_mode: DisplayMode,
+    pub vsync: bool,
+    /// window size in windowed mode, one of
+    /// [`RESOLUTIONS`] unless the window was resized by
+    /// hand
+    pub resolution: (u32, u32),
+    pub difficulty: Difficulty,
+    /// how strongly the camera shakes on impacts, from 0
+    /// (off) to 1
+    pub screen_shake: f32,
+    /// how many particles explosions spawn, from 0 (none)
+    /// to 1
+    pub particle_density: f32,
+    /// show the points earned where something was
+    /// destroyed
+    pub score_popups: bool,
+    /// append each finished run's statistics to a file
+    pub stats_export: StatsExport,
+    /// pause when the window loses focus or is minimized
+    pub pause_on_focus_loss: bool,
+    /// turns off screen shake, whatever its strength
+    pub reduce_motion: bool,
+    /// scales all ui, from 0.75 to 1.5
+    pub ui_scale: f32,
+    /// the ship picked last, preselected when choosing a
+    /// ship
+    pub ship: PlayerShipType,
+    /// the color picked last for the ship
+    pub ship_color: ShipColor,
+}
+
+/// The keys the ship is flown with
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub thrust: KeyCode,
+    pub rotate_left: KeyCode,
+    pub rotate_right: KeyCode,
+    pub fire: KeyCode,
+    pub pause: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            thrust: KeyCode::ArrowUp,
+            rotate_left: KeyCode::ArrowLeft,
+            rotate_right: KeyCode::ArrowRight,
+            fire: KeyCode::Space,
+            pause: KeyCode::Escape,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    /// in the order the settings page lists them
+    pub const ALL: [DisplayMode; 3] = [
+        DisplayMode::Windowed,
+        DisplayMode::Borderless,
+        DisplayMode::Fullscreen,
+    ];
+
+    fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => {
+                WindowMode::BorderlessFullscreen
+            }
+            DisplayMode::Fullscreen => {
+                WindowMode::Fullscreen
+            }
+        }
+    }
+}
+
+/// The window sizes offered on the settings page
+pub const RESOLUTIONS: [(u32, u32); 4] =
+    [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440)];
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// in the order the settings page lists them
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+    ];
+
+    /// Lives to start a run with, given the normal
+    /// number of starting lives. Takes effect from the
+    /// next run.
+    pub fn starting_lives(self, normal: usize) -> usize {
+        match self {
+            Difficulty::Easy => normal + 2,
+            Difficulty::Normal => normal,
+            Difficulty::Hard => {
+                normal.saturating_sub(1).max(1)
+            }
+        }
+    }
+
+    /// Scales the time between ufo shots
+    pub fn ufo_fire_interval_factor(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.6,
+        }
+    }
+}
+
+/// The file format finished runs are written in, see
+/// `stats::export`
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum StatsExport {
+    Off,
+    Csv,
+    JsonLines,
+}
+
+impl StatsExport {
+    /// in the order the settings page lists them
+    pub const ALL: [StatsExport; 3] = [
+        StatsExport::Off,
+        StatsExport::Csv,
+        StatsExport::JsonLines,
+    ];
 }
 
 impl Default for GameSettings {
     fn default() -> Self {
         GameSettings {
             audio: AudioSettings::ON,
+            master_volume: 1.0,
+            effects_volume: 1.0,
+            controls: KeyBindings::default(),
+            display_mode: DisplayMode::Windowed,
+            vsync: true,
+            resolution: RESOLUTIONS[0],
+            difficulty: Difficulty::Normal,
+            screen_shake: 1.0,
+            particle_density: 1.0,
+            score_popups: true,
+            stats_export: StatsExport::Off,
+            pause_on_focus_loss: true,
+            reduce_motion: false,
+            ui_scale: 1.0,
+            ship: PlayerShipType::B,
+            ship_color: ShipColor::Blue,
+        }
+    }
+}
+
+impl GameSettings {
+    /// The screen shake strength, taking reduced motion
+    /// into account
+    pub fn effective_screen_shake(&self) -> f32 {
+        if self.reduce_motion {
+            0.
+        } else {
+            self.screen_shake
+        }
+    }
+
+    /// Playback settings for a one-off sound effect
+    pub fn sound_effect(&self) -> PlaybackSettings {
+        PlaybackSettings::DESPAWN
+            .with_volume(Volume::new(self.effects_volume))
+    }
+}
+
+/// The saved settings, or the defaults if there are none
+/// or they can't be read
+fn load_settings() -> GameSettings {
+    let path = Path::new(SETTINGS_PATH);
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error)
+            if error.kind() == io::ErrorKind::NotFound =>
+        {
+            return GameSettings::default();
         }
+        Err(error) => {
+            warn!(
+                "failed to read {}: {error}",
+                path.display()
+            );
+            return GameSettings::default();
+        }
+    };
+    serde_json::from_str(&contents).unwrap_or_else(
+        |error| {
+            warn!(
+                "failed to parse {}: {error}",
+                path.display()
+            );
+            GameSettings::default()
+        },
+    )
+}
+
+/// Writes the settings once they've stopped changing for
+/// a moment, so dragging a slider doesn't write the file
+/// every frame
+fn save_settings(
+    settings: Res<GameSettings>,
+    time: Res<Time<Real>>,
+    mut pending: Local<Option<Timer>>,
+) {
+    if settings.is_changed() && !settings.is_added() {
+        *pending =
+            Some(Timer::from_seconds(0.5, TimerMode::Once));
+    }
+    let Some(timer) = pending.as_mut() else {
+        return;
+    };
+    if !timer.tick(time.delta()).finished() {
+        return;
+    }
+    *pending = None;
+
+    let result = serde_json::to_string_pretty(&*settings)
+        .map_err(io::Error::from)
+        .and_then(|json| fs::write(SETTINGS_PATH, json));
+    if let Err(error) = result {
+        warn!("failed to save settings: {error}");
+    }
+}
+
+fn apply_audio_settings(
+    settings: Res<GameSettings>,
+    mut global_volume: ResMut<GlobalVolume>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    // only affects sounds started from now on, which is
+    // all of them for short sound effects
+    global_volume.volume =
+        Volume::new(settings.master_volume);
+}
+
+/// Keeps [`GameSettings::resolution`] in step with the
+/// window when it's resized by hand, so the saved size is
+/// the real one
+fn track_window_size(
+    mut resized: EventReader<WindowResized>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    let Some(event) = resized.read().last() else {
+        return;
+    };
+    let Ok(window) = windows.get(event.window) else {
+        return;
+    };
+    if window.mode != WindowMode::Windowed {
+        return;
+    }
+    let size = (
+        window.resolution.physical_width(),
+        window.resolution.physical_height(),
+    );
+    if settings.resolution != size {
+        settings.resolution = size;
+    }
+}
+
+/// display mode, vsync and resolution last written to the
+/// window
+type AppliedDisplay = (DisplayMode, bool, (u32, u32));
+
+/// Only touches the window when the display settings
+/// themselves change, not whenever any setting does
+fn apply_display_settings(
+    settings: Res<GameSettings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut applied: Local<Option<AppliedDisplay>>,
+) {
+    let display = (
+        settings.display_mode,
+        settings.vsync,
+        settings.resolution,
+    );
+    if *applied == Some(display) {
+        return;
+    }
+    let Ok(mut window) = windows.get_single_mut() else {
+        warn!("no primary window to apply display settings to");
+        return;
+    };
+    *applied = Some(display);
+
+    let mode = settings.display_mode.window_mode();
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let present_mode = if settings.vsync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+    // fullscreen modes size the window themselves
+    let (width, height) = settings.resolution;
+    if settings.display_mode == DisplayMode::Windowed
+        && (window.resolution.physical_width() != width
+            || window.resolution.physical_height()
+                != height)
+    {
+        window
+            .resolution
+            .set_physical_resolution(width, height);
+    }
+}
+
+fn apply_ui_scale(
+    settings: Res<GameSettings>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if settings.is_changed() {
+        ui_scale.0 = settings.ui_scale;
     }
 }
@@ -1,12 +1,17 @@
 use bevy::prelude::*;
 use bevy_hanabi::prelude::*;
 use bevy_xpbd_2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    animation::{
+        AnimationClip, AnimationMode, SpriteAnimation,
+    },
     assets::ImageAssets,
+    collisions::GameLayer,
     controls::MovementFactor,
     kenney_assets::KenneySpriteSheetAsset,
-    lives::{Lives, RemoveLifeEvent},
+    lives::{Lives, LivesUpdate, RemoveLifeEvent},
     movement::WrappingMovement,
     ui::pause::Pausable,
     GameState, Player,
@@ -17,6 +22,7 @@ pub struct ShipPlugin;
 impl Plugin for ShipPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(PlayerShipType::B)
+            .insert_resource(ShipColor::Blue)
             .add_systems(
                 PostUpdate,
                 player_ship_destroyed_event_handler
@@ -26,26 +32,65 @@ impl Plugin for ShipPlugin {
                     .run_if(in_state(GameState::Playing)),
             )
             .add_systems(
-                PostUpdate,
+                Update,
                 spawn_ship_after_ship_destroyed
+                    .after(LivesUpdate)
                     .run_if(in_state(GameState::Playing)),
-                // if lives have changed and is not 0
-                // .run_if(resource_changed::<Lives>)
-                // .run_if(not(resource_equals(Lives(0))))
-                // if ship was just destroyed
-                // .run_if(on_event::<RemoveLifeEvent>()),
             )
             .add_event::<ShipDestroyed>();
     }
 }
 
+/// `fire01.png`, the first frame of the engine fire
+pub const ENGINE_FIRE_ATLAS_INDEX: usize = 75;
+/// The engine fire animation frames. The sheet's other
+/// `fire` frames are a larger 16px wide flame that
+/// doesn't line up with these.
+pub const ENGINE_FIRE_FRAMES: [&str; 7] = [
+    "fire01.png",
+    "fire02.png",
+    "fire03.png",
+    "fire04.png",
+    "fire05.png",
+    "fire06.png",
+    "fire07.png",
+];
+
 #[derive(Component)]
 pub struct PlayerEngineFire;
 
+impl PlayerEngineFire {
+    /// The flickering flame behind the ship, cycling
+    /// through [`ENGINE_FIRE_FRAMES`]
+    pub fn animation(
+        space_sheet: &KenneySpriteSheetAsset,
+    ) -> SpriteAnimation {
+        SpriteAnimation::new(
+            AnimationClip::from_names(
+                space_sheet,
+                "fire",
+                &ENGINE_FIRE_FRAMES,
+            )
+            .expect("space_sheet should have fire frames"),
+            15.,
+            AnimationMode::Loop,
+        )
+    }
+}
+
 #[derive(Event)]
 pub struct ShipDestroyed {
     pub destroyed_at: Transform,
     pub ship_type: PlayerShipType,
+    pub cause: DeathCause,
+}
+
+/// What the player's ship ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathCause {
+    Meteor,
+    Ufo,
+    UfoLaser,
 }
 
 #[derive(Bundle)]
@@ -54,32 +99,164 @@ pub struct ShipBundle {
     pub texture_atlas: TextureAtlas,
     pub player: Player,
     pub ship_type: PlayerShipType,
+    pub ship_color: ShipColor,
     pub collider: Collider,
+    pub collision_layers: CollisionLayers,
     pub wrapping_movement: WrappingMovement,
 }
 
-#[derive(Resource, Component, Clone)]
+#[derive(
+    Debug,
+    Resource,
+    Component,
+    Clone,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
 pub enum PlayerShipType {
     A,
     B,
     C,
 }
 
-impl PlayerShipType {
-    pub fn base_atlas_index(&self) -> usize {
-        match &self {
-            PlayerShipType::A => 200,
-            PlayerShipType::B => 207,
-            PlayerShipType::C => 214,
+/// The colors every hull comes in
+#[derive(
+    Debug,
+    Resource,
+    Component,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub enum ShipColor {
+    Blue,
+    Green,
+    Orange,
+    Red,
+}
+
+impl ShipColor {
+    /// in the order the choose ship screen lists them
+    pub const ALL: [ShipColor; 4] = [
+        ShipColor::Blue,
+        ShipColor::Green,
+        ShipColor::Orange,
+        ShipColor::Red,
+    ];
+
+    /// The player's laser. There are no orange lasers,
+    /// so orange ships fire red ones.
+    pub fn laser_texture(self) -> &'static str {
+        match self {
+            ShipColor::Blue => "laserBlue01.png",
+            ShipColor::Green => "laserGreen11.png",
+            ShipColor::Orange | ShipColor::Red => {
+                "laserRed01.png"
+            }
         }
     }
-    pub fn life_atlas_index(&self) -> usize {
-        match &self {
-            PlayerShipType::A => 188,
-            PlayerShipType::B => 192,
-            PlayerShipType::C => 196,
+
+    pub fn laser_atlas_index(
+        self,
+        space_sheet: &KenneySpriteSheetAsset,
+    ) -> usize {
+        space_sheet
+            .index_of(self.laser_texture())
+            .expect("space_sheet should have every laser")
+    }
+}
+
+/// `playerShip{hull}_{color}.png`, by hull then
+/// [`ShipColor`]
+const SHIP_TEXTURES: [[&str; 4]; 3] = [
+    [
+        "playerShip1_blue.png",
+        "playerShip1_green.png",
+        "playerShip1_orange.png",
+        "playerShip1_red.png",
+    ],
+    [
+        "playerShip2_blue.png",
+        "playerShip2_green.png",
+        "playerShip2_orange.png",
+        "playerShip2_red.png",
+    ],
+    [
+        "playerShip3_blue.png",
+        "playerShip3_green.png",
+        "playerShip3_orange.png",
+        "playerShip3_red.png",
+    ],
+];
+
+/// `playerLife{hull}_{color}.png`, by hull then
+/// [`ShipColor`]
+const LIFE_TEXTURES: [[&str; 4]; 3] = [
+    [
+        "playerLife1_blue.png",
+        "playerLife1_green.png",
+        "playerLife1_orange.png",
+        "playerLife1_red.png",
+    ],
+    [
+        "playerLife2_blue.png",
+        "playerLife2_green.png",
+        "playerLife2_orange.png",
+        "playerLife2_red.png",
+    ],
+    [
+        "playerLife3_blue.png",
+        "playerLife3_green.png",
+        "playerLife3_orange.png",
+        "playerLife3_red.png",
+    ],
+];
+
+impl PlayerShipType {
+    fn hull(&self) -> usize {
+        match self {
+            PlayerShipType::A => 0,
+            PlayerShipType::B => 1,
+            PlayerShipType::C => 2,
         }
     }
+    /// The ship's sprite in `color`
+    pub fn texture(
+        &self,
+        color: ShipColor,
+    ) -> &'static str {
+        SHIP_TEXTURES[self.hull()][color as usize]
+    }
+    /// The life icon matching [`Self::texture`]
+    pub fn life_texture(
+        &self,
+        color: ShipColor,
+    ) -> &'static str {
+        LIFE_TEXTURES[self.hull()][color as usize]
+    }
+    pub fn atlas_index(
+        &self,
+        color: ShipColor,
+        space_sheet: &KenneySpriteSheetAsset,
+    ) -> usize {
+        space_sheet
+            .index_of(self.texture(color))
+            .expect("space_sheet should have every ship")
+    }
+    pub fn life_atlas_index(
+        &self,
+        color: ShipColor,
+        space_sheet: &KenneySpriteSheetAsset,
+    ) -> usize {
+        space_sheet
+            .index_of(self.life_texture(color))
+            .expect("space_sheet should have life icons")
+    }
     pub fn all_ships() -> Vec<PlayerShipType> {
         vec![
             PlayerShipType::A,
@@ -147,6 +324,7 @@ fn player_ship_destroyed_event_handler(
     for ShipDestroyed {
         destroyed_at,
         ship_type,
+        ..
     } in &mut events.read()
     {
         effect_transform.translation =
@@ -173,10 +351,19 @@ fn spawn_ship_after_ship_destroyed(
     mut commands: Commands,
     images: Res<ImageAssets>,
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
+    mut life_events: EventReader<RemoveLifeEvent>,
     lives: Res<Lives>,
     player_ship_type: Res<PlayerShipType>,
+    ship_color: Res<ShipColor>,
 ) {
-    if !lives.is_changed() || lives.0 == 0 || lives.0 == 3 {
+    // respawn once a lost life has been taken off, if
+    // any are left. Watching `Lives` itself would miss a
+    // death and an extra life in the same frame.
+    if life_events.is_empty() {
+        return;
+    }
+    life_events.clear();
+    if lives.0 == 0 {
         return;
     }
     let Some(space_sheet) = sheets.get(&images.space_sheet)
@@ -200,12 +387,13 @@ fn spawn_ship_after_ship_destroyed(
                 ..default()
             },
             TextureAtlas {
-                index: 74,
+                index: ENGINE_FIRE_ATLAS_INDEX,
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             PlayerEngineFire,
+            PlayerEngineFire::animation(space_sheet),
         ))
         .id();
 
@@ -217,14 +405,18 @@ fn spawn_ship_after_ship_destroyed(
                 ..default()
             },
             texture_atlas: TextureAtlas {
-                index: player_ship_type.base_atlas_index(),
+                index: player_ship_type
+                    .atlas_index(*ship_color, space_sheet),
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
             },
             player: Player,
             ship_type: player_ship_type.clone(),
+            ship_color: *ship_color,
             collider: player_ship_type.collider(),
+            collision_layers: GameLayer::Player
+                .collision_layers(),
             wrapping_movement: WrappingMovement,
         })
         .add_child(engine_fire);
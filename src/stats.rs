@@ -0,0 +1,253 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    controls::LaserFired,
+    levels::Level,
+    meteors::{MeteorDestroyed, MeteorType},
+    ship::{DeathCause, ShipDestroyed},
+    ufo::UfoDestroyed,
+    ui::pause::Pausable,
+    GameState, Player,
+};
+
+pub mod export;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .init_resource::<BestTime>()
+            .add_systems(
+                Update,
+                (
+                    count_shots,
+                    count_kills,
+                    record_deaths,
+                    (track_time_alive, track_level_time)
+                        .run_if(resource_equals(
+                            Pausable::NotPaused,
+                        )),
+                    render_time_display
+                        .after(track_time_alive),
+                )
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (reset_stats, spawn_time_display),
+            )
+            .add_systems(
+                OnExit(GameState::Playing),
+                remove_time_display,
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                (finish_run, export::export_run_stats)
+                    .chain(),
+            );
+    }
+}
+
+/// How the current (or last finished) run went
+#[derive(Resource, Debug, Default, Clone)]
+pub struct RunStats {
+    pub shots_fired: usize,
+    pub hits: usize,
+    /// meteors the player shot down
+    pub meteors_destroyed: MeteorCounts,
+    /// meteors ufo lasers shot down
+    pub meteors_destroyed_by_ufos: usize,
+    pub ufos_destroyed: usize,
+    /// how long each lost life lasted, in order
+    pub lives: Vec<Duration>,
+    /// what each lost life ran into, in order
+    pub deaths: Vec<DeathCause>,
+    /// time spent on each level, starting at level 1
+    pub level_durations: Vec<Duration>,
+    pub level_reached: usize,
+    /// time alive since the last life was lost
+    current_life: Duration,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MeteorCounts {
+    pub big: usize,
+    pub medium: usize,
+    pub small: usize,
+}
+
+impl MeteorCounts {
+    pub fn total(&self) -> usize {
+        self.big + self.medium + self.small
+    }
+}
+
+impl RunStats {
+    /// Hits per shot fired, from 0 to 1
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
+    /// Total time spent alive over every life
+    pub fn time_alive(&self) -> Duration {
+        self.lives.iter().sum::<Duration>()
+            + self.current_life
+    }
+}
+
+/// The longest time alive of any run this session
+#[derive(Resource, Debug, Default, Deref)]
+pub struct BestTime(pub Duration);
+
+/// Formats a duration as `m:ss`
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn reset_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+/// Time alive this run, and the best so far, in the top
+/// right corner while playing
+#[derive(Component)]
+struct TimeDisplay;
+
+fn spawn_time_display(mut commands: Commands) {
+    let style = TextStyle {
+        font_size: 30.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new("Time ", style.clone()),
+            TextSection::new("0:00", style.clone()),
+            TextSection::new("\nBest ", style.clone()),
+            TextSection::new("0:00", style),
+        ])
+        .with_text_justify(JustifyText::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(20.),
+            right: Val::Px(20.),
+            ..default()
+        }),
+        TimeDisplay,
+    ));
+}
+
+fn render_time_display(
+    stats: Res<RunStats>,
+    best_time: Res<BestTime>,
+    mut query: Query<&mut Text, With<TimeDisplay>>,
+) {
+    for mut text in &mut query {
+        text.sections[1].value =
+            format_duration(stats.time_alive());
+        text.sections[3].value =
+            format_duration(**best_time);
+    }
+}
+
+fn remove_time_display(
+    mut commands: Commands,
+    query: Query<Entity, With<TimeDisplay>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn count_shots(
+    mut stats: ResMut<RunStats>,
+    mut fired: EventReader<LaserFired>,
+) {
+    stats.shots_fired += fired.read().count();
+}
+
+fn count_kills(
+    mut stats: ResMut<RunStats>,
+    mut meteors: EventReader<MeteorDestroyed>,
+    mut ufos: EventReader<UfoDestroyed>,
+) {
+    for meteor in meteors.read() {
+        if meteor.destroyed_by.player().is_none() {
+            stats.meteors_destroyed_by_ufos += 1;
+            continue;
+        }
+        stats.hits += 1;
+        let counts = &mut stats.meteors_destroyed;
+        match meteor.destroyed_type {
+            MeteorType::Big => counts.big += 1,
+            MeteorType::Medium => counts.medium += 1,
+            MeteorType::Small => counts.small += 1,
+        }
+    }
+    for ufo in ufos.read() {
+        if ufo.destroyed_by.player().is_none() {
+            continue;
+        }
+        stats.hits += 1;
+        stats.ufos_destroyed += 1;
+    }
+}
+
+fn record_deaths(
+    mut stats: ResMut<RunStats>,
+    mut deaths: EventReader<ShipDestroyed>,
+) {
+    for death in deaths.read() {
+        let life = std::mem::take(&mut stats.current_life);
+        stats.lives.push(life);
+        stats.deaths.push(death.cause);
+    }
+}
+
+/// Only count time while a ship is on screen, not while
+/// waiting for the next one to spawn
+fn track_time_alive(
+    time: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    player: Query<(), With<Player>>,
+) {
+    if !player.is_empty() {
+        stats.current_life += time.delta();
+    }
+}
+
+fn track_level_time(
+    time: Res<Time>,
+    level: Res<Level>,
+    mut stats: ResMut<RunStats>,
+) {
+    let index = level.saturating_sub(1);
+    if stats.level_durations.len() <= index {
+        stats
+            .level_durations
+            .resize(index + 1, Duration::ZERO);
+    }
+    stats.level_durations[index] += time.delta();
+}
+
+fn finish_run(
+    mut stats: ResMut<RunStats>,
+    mut best_time: ResMut<BestTime>,
+    level: Res<Level>,
+) {
+    // the last death can arrive after the game is
+    // already over
+    if !stats.current_life.is_zero() {
+        let life = std::mem::take(&mut stats.current_life);
+        stats.lives.push(life);
+    }
+    stats.level_reached = **level;
+    best_time.0 = best_time.0.max(stats.time_alive());
+    info!(?stats, "run finished");
+}
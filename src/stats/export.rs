@@ -0,0 +1,136 @@
+//! Appends every finished run to a local file for
+//! balancing playtests, one run per line:
+//!
+//! - [`StatsExport::Csv`] writes `run_stats.csv`, with a
+//!   header when the file is new. List columns are `;`
+//!   separated.
+//! - [`StatsExport::JsonLines`] writes one json object per
+//!   line to `run_stats.jsonl`.
+//!
+//! Files are written to the working directory.
+
+use bevy::prelude::*;
+use serde::Serialize;
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+};
+
+use super::RunStats;
+use crate::{
+    levels::RunRng,
+    scores::Scores,
+    settings::{GameSettings, StatsExport},
+    ship::PlayerShipType,
+};
+
+const CSV_HEADER: &str = "seed,ship_type,level_reached,score,shots_fired,hits,time_alive_secs,level_durations_secs,death_causes";
+
+#[derive(Serialize)]
+struct RunRecord {
+    seed: u64,
+    ship_type: String,
+    level_reached: usize,
+    score: usize,
+    shots_fired: usize,
+    hits: usize,
+    time_alive_secs: f32,
+    level_durations_secs: Vec<f32>,
+    death_causes: Vec<String>,
+}
+
+impl RunRecord {
+    fn to_csv_row(&self) -> String {
+        let level_durations = self
+            .level_durations_secs
+            .iter()
+            .map(|secs| format!("{secs:.2}"))
+            .collect::<Vec<_>>()
+            .join(";");
+        format!(
+            "{},{},{},{},{},{},{:.2},{},{}",
+            self.seed,
+            self.ship_type,
+            self.level_reached,
+            self.score,
+            self.shots_fired,
+            self.hits,
+            self.time_alive_secs,
+            level_durations,
+            self.death_causes.join(";"),
+        )
+    }
+}
+
+pub(super) fn export_run_stats(
+    settings: Res<GameSettings>,
+    stats: Res<RunStats>,
+    scores: Res<Scores>,
+    rng: Res<RunRng>,
+    ship_type: Res<PlayerShipType>,
+) {
+    let record = RunRecord {
+        seed: rng.seed(),
+        ship_type: format!("{:?}", *ship_type),
+        level_reached: stats.level_reached,
+        score: scores.current,
+        shots_fired: stats.shots_fired,
+        hits: stats.hits,
+        time_alive_secs: stats.time_alive().as_secs_f32(),
+        level_durations_secs: stats
+            .level_durations
+            .iter()
+            .map(|duration| duration.as_secs_f32())
+            .collect(),
+        death_causes: stats
+            .deaths
+            .iter()
+            .map(|cause| format!("{cause:?}"))
+            .collect(),
+    };
+
+    let result = match settings.stats_export {
+        StatsExport::Off => return,
+        StatsExport::Csv => append_line(
+            Path::new("run_stats.csv"),
+            Some(CSV_HEADER),
+            &record.to_csv_row(),
+        ),
+        StatsExport::JsonLines => {
+            serde_json::to_string(&record)
+                .map_err(io::Error::from)
+                .and_then(|line| {
+                    append_line(
+                        Path::new("run_stats.jsonl"),
+                        None,
+                        &line,
+                    )
+                })
+        }
+    };
+    if let Err(error) = result {
+        warn!("failed to export run statistics: {error}");
+    }
+}
+
+/// Append `line` to `path`, starting a new file with
+/// `header`
+fn append_line(
+    path: &Path,
+    header: Option<&str>,
+    line: &str,
+) -> io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if let Some(header) = header {
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{header}")?;
+        }
+    }
+    writeln!(file, "{line}")?;
+    info!("exported run statistics to {}", path.display());
+    Ok(())
+}
@@ -6,9 +6,14 @@ use bevy_xpbd_2d::plugins::collision::Collider;
 use rand::Rng;
 
 use crate::{
-    assets::ImageAssets, controls::Laser,
+    assets::ImageAssets,
+    collisions::GameLayer,
+    controls::{Laser, Shooter},
     kenney_assets::KenneySpriteSheetAsset,
-    movement::WrappingMovement, ui::pause::Pausable,
+    levels::RunRng,
+    movement::WrappingMovement,
+    settings::GameSettings,
+    ui::pause::Pausable,
     GameState, Player,
 };
 
@@ -19,6 +24,9 @@ impl Plugin for UfoPlugin {
         app.add_systems(
             Update,
             (ufo_movement, ufo_weapon_system)
+                .run_if(resource_equals(
+                    Pausable::NotPaused,
+                ))
                 .run_if(in_state(GameState::Playing)),
         )
         .add_systems(
@@ -39,6 +47,17 @@ impl Plugin for UfoPlugin {
     }
 }
 
+/// `ufoRed.png`
+pub const UFO_ATLAS_INDEX: usize = 260;
+/// `laserRed01.png`
+pub const UFO_LASER_ATLAS_INDEX: usize = 137;
+/// The sub-texture used to size the ufo collider
+pub const UFO_COLLIDER_TEXTURE: &str = "ufoBlue.png";
+
+/// How far a ufo drifts each second, on top of flying
+/// forward
+const UFO_DRIFT: Vec2 = Vec2::new(60., 60.);
+
 #[derive(Component)]
 pub struct Ufo;
 
@@ -51,9 +70,8 @@ fn periodically_spawn_ufo(
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
     window: Query<&Window, With<PrimaryWindow>>,
     query: Query<&Ufo>,
+    mut rng: ResMut<RunRng>,
 ) {
-    let mut rng = rand::thread_rng();
-
     if !query.is_empty() || rng.gen::<f32>() < 0.2 {
         info!("did not spawn ufo");
         return;
@@ -74,7 +92,7 @@ fn periodically_spawn_ufo(
         .textures
         .iter()
         .find(|sub_texture| {
-            sub_texture.name == "ufoBlue.png"
+            sub_texture.name == UFO_COLLIDER_TEXTURE
         })
         .expect(
             "space_sheet should have a valid ufo texture",
@@ -90,12 +108,13 @@ fn periodically_spawn_ufo(
             ..default()
         },
         TextureAtlas {
-            index: 260, // red ufo
+            index: UFO_ATLAS_INDEX,
             layout: space_sheet
                 .texture_atlas_layout
                 .clone(),
         },
         Collider::circle(ufo_dimensions.width as f32 / 2.),
+        GameLayer::Enemy.collision_layers(),
         WrappingMovement,
         Ufo,
     ));
@@ -105,13 +124,14 @@ fn ufo_movement(
     mut query: Query<&mut Transform, With<Ufo>>,
     time: Res<Time>,
 ) {
-    let preexisting_movement_factor = Vec2::new(1., 1.);
     for mut transform in &mut query {
         let ufo_facing_direction = Vec3::X;
         // transform.rotation * Vec3::Y;
-        let translation_delta = preexisting_movement_factor
-            + ufo_facing_direction.xy()
-                * time.delta_seconds();
+        // scaled by the delta, so a paused clock stops
+        // the ufo in place
+        let translation_delta = (UFO_DRIFT
+            + ufo_facing_direction.xy())
+            * time.delta_seconds();
         transform.translation.x += translation_delta.x;
         transform.translation.y += translation_delta.y
             * time.elapsed_seconds().sin();
@@ -121,6 +141,7 @@ fn ufo_movement(
 #[derive(Debug, Event)]
 pub struct UfoDestroyed {
     pub destroyed_at: Transform,
+    pub destroyed_by: Shooter,
 }
 
 fn ufo_destroyed_event_handler(
@@ -155,7 +176,8 @@ fn ufo_destroyed_event_handler(
         return;
     };
 
-    for UfoDestroyed { destroyed_at } in &mut events.read()
+    for UfoDestroyed { destroyed_at, .. } in
+        &mut events.read()
     {
         effect_transform.translation =
             destroyed_at.translation;
@@ -175,6 +197,7 @@ fn ufo_destroyed_event_handler(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn ufo_weapon_system(
     mut commands: Commands,
     time: Res<Time>,
@@ -183,6 +206,7 @@ fn ufo_weapon_system(
     images: Res<ImageAssets>,
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
     mut last_shot: Local<Option<Duration>>,
+    settings: Res<GameSettings>,
 ) {
     let space_sheet =
         sheets.get(&images.space_sheet).unwrap();
@@ -221,7 +245,11 @@ fn ufo_weapon_system(
     let can_shoot = last_shot.is_none() || {
         if let Some(shot) = *last_shot {
             time.elapsed() - shot
-                > Duration::from_millis(2000)
+                > Duration::from_millis(2000).mul_f32(
+                    settings
+                        .difficulty
+                        .ufo_fire_interval_factor(),
+                )
         } else {
             false
         }
@@ -252,18 +280,20 @@ fn ufo_weapon_system(
                 layout: space_sheet
                     .texture_atlas_layout
                     .clone(),
-                index: 137,
+                index: UFO_LASER_ATLAS_INDEX,
             },
             Laser {
                 movement_factor: movement_factor,
                 speed: 300.,
             },
             UfoOwned,
+            Shooter::Ufo,
             Collider::triangle(
                 Vec2::new(0., -27.),
                 Vec2::new(4.5, 27.),
                 Vec2::new(-4.5, 27.),
             ),
+            GameLayer::EnemyProjectile.collision_layers(),
         ));
     }
 }
@@ -1,50 +1,65 @@
-use crate::{
-    assets::{AudioAssets, FontAssets, ImageAssets},
-    colors,
-    settings::{AudioSettings, GameSettings},
-    GameState,
-};
+use crate::{assets::ImageAssets, GameState};
 use bevy::prelude::*;
 
-mod button;
+pub(crate) mod button;
 pub mod choose_ship;
+pub mod focus;
+pub mod game_over;
+pub mod menu_stack;
+pub mod nine_slice;
 pub mod pause;
-use button::SpawnButton;
+pub mod settings_page;
+pub mod widgets;
+use button::{ButtonAction, SpawnButton};
+use focus::{FocusPlugin, FocusSet, MenuFocus};
+use game_over::GameOverPlugin;
+use menu_stack::{MenuStack, MenuStackPlugin};
+use nine_slice::{NineSliceImage, NineSlicePlugin};
+use settings_page::{
+    SettingsPagePlugin, SpawnSettingsPage,
+};
+use widgets::WidgetsPlugin;
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.insert_resource(MenuPage::Main)
-            .add_systems(
-                OnExit(GameState::AssetLoading),
-                main_menu,
-            )
-            .add_systems(
-                OnEnter(GameState::Menu),
-                show_menu,
-            )
-            .add_systems(OnExit(GameState::Menu), hide_menu)
-            .add_systems(
-                Update,
-                button::text_button_system.run_if(not(
-                    in_state(GameState::AssetLoading),
-                )),
-            )
-            .add_systems(
-                Update,
-                (
-                    change_menu,
-                    audio_state,
-                    // snake_selector_interaction,
-                    // update_current_snake,
-                )
-                    .run_if(in_state(GameState::Menu)),
-            );
+        app.add_plugins((
+            NineSlicePlugin,
+            FocusPlugin,
+            MenuStackPlugin,
+            GameOverPlugin,
+            WidgetsPlugin,
+            SettingsPagePlugin,
+        ));
+        app.add_systems(
+            OnExit(GameState::AssetLoading),
+            main_menu,
+        )
+        .add_systems(OnEnter(GameState::Menu), show_menu)
+        .add_systems(OnExit(GameState::Menu), hide_menu)
+        .add_systems(
+            Update,
+            button::text_button_system
+                .after(FocusSet)
+                .run_if(not(in_state(
+                    GameState::AssetLoading,
+                ))),
+        )
+        .add_systems(
+            Update,
+            show_pages_over_game
+                .run_if(in_state(GameState::Playing)),
+        );
     }
 }
 
-#[derive(Resource, Component, Debug, PartialEq)]
+/// Nine-sliced background of the menu panels, from
+/// the ui sheet
+pub const PANEL_TEXTURE: &str = "glassPanel.png";
+
+/// A page of the main menu, see [`MenuStack`]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuPage {
     Main,
     Settings,
@@ -54,8 +69,12 @@ pub enum MenuPage {
 struct MainMenu;
 
 fn show_menu(
+    mut menu_stack: ResMut<MenuStack>,
     mut menu: Query<&mut Visibility, With<MainMenu>>,
 ) {
+    if menu_stack.depth() > 1 {
+        menu_stack.clear();
+    }
     let mut menu = menu.single_mut();
     *menu = Visibility::Visible;
 }
@@ -65,68 +84,28 @@ fn hide_menu(
     let mut menu = menu.single_mut();
     *menu = Visibility::Hidden;
 }
-fn change_menu(
-    menu: Res<MenuPage>,
-    mut menu_pages: Query<(&MenuPage, &mut Visibility)>,
+/// The pause menu opens pages of the main menu, like
+/// settings, on top of the game
+fn show_pages_over_game(
+    menu_stack: Res<MenuStack>,
+    mut menu: Query<&mut Visibility, With<MainMenu>>,
 ) {
-    if menu.is_changed() {
-        for (page, mut visibility) in menu_pages.iter_mut()
-        {
-            if page == &*menu {
-                *visibility = Visibility::Inherited;
-            } else {
-                *visibility = Visibility::Hidden;
-            }
-        }
+    if !menu_stack.is_changed() {
+        return;
     }
-}
-
-fn audio_state(
-    commands: Commands,
-    mut interaction_query: Query<
-        (&Interaction, &mut UiImage),
-        (
-            Changed<Interaction>,
-            With<Button>,
-            With<AudioSettingsCheckbox>,
-        ),
-    >,
-    images: Res<ImageAssets>,
-    mut settings: ResMut<GameSettings>,
-    sounds: Res<AudioAssets>,
-) {
-    for (interaction, mut image) in &mut interaction_query {
-        if interaction == &Interaction::Pressed {
-            if settings.audio == AudioSettings::ON {
-                // commands.spawn(AudioBundle {
-                //     source:
-                // sounds.apple.clone(),
-                //     ..default()
-                // });
-            }
-            settings.audio = match settings.audio {
-                AudioSettings::ON => AudioSettings::OFF,
-                AudioSettings::OFF => AudioSettings::ON,
+    for mut visibility in &mut menu {
+        *visibility =
+            if menu_stack.current() != MenuPage::Main {
+                Visibility::Visible
+            } else {
+                Visibility::Hidden
             };
-            *image = UiImage::new(match settings.audio {
-                AudioSettings::ON => {
-                    images.box_checked.clone()
-                }
-                AudioSettings::OFF => {
-                    images.box_unchecked.clone()
-                }
-            });
-        }
     }
 }
 
-#[derive(Component)]
-struct AudioSettingsCheckbox;
-
 pub fn main_menu(
     mut commands: Commands,
     images: Res<ImageAssets>,
-    fonts: Res<FontAssets>,
 ) {
     commands
         .spawn((
@@ -144,17 +123,9 @@ pub fn main_menu(
             MainMenu,
         ))
         .with_children(|parent| {
-            let panel_slicer = TextureSlicer {
-                border: BorderRect::square(20.0),
-                center_scale_mode: SliceScaleMode::Stretch,
-                sides_scale_mode: SliceScaleMode::Stretch,
-                max_corner_scale: 1.0,
-            };
-     
             parent
                 .spawn((
-                    ImageBundle {
-                        image: images.panel_glass.clone().into(),
+                    NodeBundle {
                         style: Style {
                             width: Val::Px(360.0),
                             height: Val::Px(500.0),
@@ -165,103 +136,45 @@ pub fn main_menu(
                             position_type:
                                 PositionType::Absolute,
                             align_self: AlignSelf::Center,
-                            border: UiRect::all(Val::Px(
+                            padding: UiRect::all(Val::Px(
                                 10.0,
                             )),
                             ..default()
                         },
                         ..default()
                     },
-                    ImageScaleMode::Sliced(panel_slicer.clone()),
+                    NineSliceImage::new(
+                        images.ui_sheet.clone(),
+                        PANEL_TEXTURE,
+                    ),
                     MenuPage::Main,
+                    MenuFocus::default(),
                 ))
                 .with_children(|parent| {
                     let entity = parent.parent_entity();
-                    parent.add_command(SpawnButton{
+                    parent.add_command(SpawnButton {
                         parent: entity,
-                        text: "New Game"
+                        text: "New Game",
+                        action: ButtonAction::ChangeState(
+                            GameState::ChooseShip,
+                        ),
                     });
-                    parent.add_command(SpawnButton{
+                    parent.add_command(SpawnButton {
                         parent: entity,
-                        text: "Settings"
+                        text: "Settings",
+                        action: ButtonAction::PushPage(
+                            MenuPage::Settings,
+                        ),
                     });
-                    parent.add_command(SpawnButton{
-                        parent: entity,
-                        text: "Exit"
-                    });
-                });
-            parent
-                .spawn((
-                    ImageBundle {
-                        image: images.panel_glass.clone().into(),
-                        visibility: Visibility::Hidden,
-                        style: Style {
-                            width: Val::Px(360.0),
-                            height: Val::Px(500.0),
-                            flex_direction:
-                                FlexDirection::Column,
-                            justify_content:
-                                JustifyContent::SpaceBetween,
-                            border: UiRect::all(Val::Px(
-                                10.0,
-                            )),
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    ImageScaleMode::Sliced(panel_slicer.clone()),
-                    MenuPage::Settings,
-                ))
-                .with_children(|parent| {
-                    let entity = parent.parent_entity();
-                    parent.add_command(SpawnButton{
+                    parent.add_command(SpawnButton {
                         parent: entity,
-                        text: "Back"
+                        text: "Exit",
+                        action: ButtonAction::Exit,
                     });
-                    parent
-                        .spawn(NodeBundle {
-                            style: Style {
-                                width: Val::Auto,
-                                height: Val::Px(25.0),
-                                ..default()
-                            },
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                ButtonBundle {
-                                    style: Style {
-                                        width: Val::Px(25.0),
-                                        height: Val::Px(25.0),
-                                        margin:
-                                            UiRect::right(
-                                                Val::Px(
-                                                    10.0,
-                                                ),
-                                            ),
-                                        ..default()
-                                    },
-                                    image: UiImage::new(
-                                        images
-                                            .box_checked
-                                            .clone(),
-                                    ),
-                                    ..default()
-                                },
-                                AudioSettingsCheckbox,
-                            ));
-                            parent.spawn(
-                                TextBundle::from_section(
-                                    "Play Audio",
-                                    TextStyle {
-                                        font:fonts.roboto.clone(),
-                                        font_size: 25.0,
-                                        color: colors::TEXT,
-                                    },
-                                ),
-                            );
-                        });
-
                 });
+            let menu = parent.parent_entity();
+            parent.add_command(SpawnSettingsPage {
+                parent: menu,
+            });
         });
 }
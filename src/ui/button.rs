@@ -1,15 +1,19 @@
-use super::MenuPage;
+use super::{
+    focus::Focusable, menu_stack::MenuStack,
+    nine_slice::NineSliceImage, MenuPage,
+};
 use crate::{
     assets::{AudioAssets, FontAssets, ImageAssets},
-    kenney_assets::KenneySpriteSheetAsset,
     settings::{AudioSettings, GameSettings},
     GameState,
 };
 use bevy::{
-    app::AppExit, ecs::system::Command, prelude::*,
+    app::AppExit,
+    ecs::system::{Command, SystemId, SystemParam},
+    prelude::*,
 };
 
-const HOVERED_BUTTON: Color = Color::Hsla {
+pub(crate) const HOVERED_BUTTON: Color = Color::Hsla {
     hue: 0.0,
     saturation: 0.0,
     lightness: 0.90,
@@ -22,32 +26,73 @@ const PRESSED_BUTTON: Color = Color::Hsla {
     alpha: 1.0,
 };
 
+/// Nine-sliced background of every [`SpawnButton`]
+pub const BUTTON_TEXTURE: &str = "buttonBlue.png";
+
 #[derive(Component)]
 pub struct TextButton;
 
+/// What pressing a [`SpawnButton`] does. Buttons are
+/// dispatched on this rather than on their label.
+#[derive(Component, Debug, Clone)]
+pub enum ButtonAction {
+    ChangeState(GameState),
+    /// open a page of the main menu on top of the
+    /// current one
+    PushPage(MenuPage),
+    /// go back to the previous page of the main menu
+    PopPage,
+    /// run a one-shot system, registered with
+    /// `World::register_system`
+    Run(SystemId),
+    Exit,
+}
+
+/// Carries out [`ButtonAction`]s, for systems that
+/// trigger them other than by pressing the button
+#[derive(SystemParam)]
+pub struct ButtonActions<'w, 's> {
+    commands: Commands<'w, 's>,
+    exit: EventWriter<'w, AppExit>,
+    menu_stack: ResMut<'w, MenuStack>,
+    next_state: ResMut<'w, NextState<GameState>>,
+}
+
+impl ButtonActions<'_, '_> {
+    pub fn run(&mut self, action: &ButtonAction) {
+        match action {
+            ButtonAction::ChangeState(state) => {
+                self.next_state.set(*state);
+            }
+            ButtonAction::PushPage(page) => {
+                self.menu_stack.push(*page);
+            }
+            ButtonAction::PopPage => {
+                self.menu_stack.pop();
+            }
+            ButtonAction::Run(system) => {
+                self.commands.run_system(*system);
+            }
+            ButtonAction::Exit => {
+                self.exit.send(AppExit);
+            }
+        }
+    }
+}
+
 pub fn text_button_system(
     mut commands: Commands,
     mut interaction_query: Query<
-        (
-            &Interaction,
-            // in 0.14, BackgroundColor is no longer used
-            // for tinting https://github.com/bevyengine/bevy/pull/11165
-            &mut BackgroundColor,
-            &Children,
-        ),
+        (&Interaction, &mut NineSliceImage, &ButtonAction),
         (Changed<Interaction>, With<TextButton>),
     >,
-    text_query: Query<&Text>,
-    mut exit: EventWriter<AppExit>,
-    mut menu_page: ResMut<MenuPage>,
+    mut actions: ButtonActions,
     settings: Res<GameSettings>,
     sounds: Res<AudioAssets>,
-    mut next_state: ResMut<NextState<GameState>>,
 ) {
-    for (interaction, mut color, children) in
+    for (interaction, mut image, action) in
         &mut interaction_query
     {
-        let text = text_query.get(children[0]).unwrap();
         match *interaction {
             Interaction::Pressed => {
                 if settings.audio == AudioSettings::ON {
@@ -58,45 +103,20 @@ pub fn text_button_system(
                     //     ..default()
                     // });
                 }
-                *color = PRESSED_BUTTON.into();
-                match text.sections[0].value.as_str() {
-                    "New Game" => {
-                        next_state
-                            .set(GameState::ChooseShip);
-                    }
-                    "Settings" => {
-                        *menu_page = MenuPage::Settings;
-                        // Show Settings Page
-                    }
-                    "Exit" => {
-                        exit.send(AppExit);
-                    }
-                    "Back" => {
-                        *menu_page = MenuPage::Main;
-                        // Show Main Menu Page
-                    }
-                    "Play" => {
-                        next_state
-                            .set(GameState::ChooseShip);
-                    }
-                    _ => {
-                        unimplemented!(
-                            "Button goes nowhere"
-                        );
-                    }
-                }
+                image.color = PRESSED_BUTTON;
+                actions.run(action);
             }
             Interaction::Hovered => {
                 if settings.audio == AudioSettings::ON {
                     commands.spawn(AudioBundle {
                         source: sounds.menu_click.clone(),
-                        ..default()
+                        settings: settings.sound_effect(),
                     });
                 }
-                *color = HOVERED_BUTTON.into();
+                image.color = HOVERED_BUTTON;
             }
             Interaction::None => {
-                *color = Color::WHITE.into();
+                image.color = Color::WHITE;
             }
         }
     }
@@ -105,6 +125,7 @@ pub fn text_button_system(
 pub struct SpawnButton<T: Into<String>> {
     pub text: T,
     pub parent: Entity,
+    pub action: ButtonAction,
 }
 
 impl<T: Into<String> + Send + 'static> Command
@@ -116,27 +137,11 @@ impl<T: Into<String> + Send + 'static> Command
             .unwrap()
             .alfa_slab_one_regular
             .clone();
-        let space_sheet = {
-            let images = world
-                .get_resource::<ImageAssets>()
-                .expect("image assets to have been loaded");
-
-            let spritesheets = world
-                .get_resource::<Assets<KenneySpriteSheetAsset>>()
-                .expect("sprite sheet assets collection to exist");
-
-            spritesheets
-                .get(&images.space_sheet)
-                .expect("expect space sheet to have loaded")
-        };
-
-        // TODO: TextureAtlas Panel Slicers? Does it work?
-        // let panel_slicer = TextureSlicer {
-        //     border: BorderRect::square(10.0),
-        //     center_scale_mode: SliceScaleMode::Stretch,
-        //     sides_scale_mode: SliceScaleMode::Stretch,
-        //     max_corner_scale: 1.0,
-        // };
+        let space_sheet = world
+            .get_resource::<ImageAssets>()
+            .expect("image assets to have been loaded")
+            .space_sheet
+            .clone();
 
         world
             .spawn((
@@ -152,21 +157,18 @@ impl<T: Into<String> + Send + 'static> Command
                         align_items: AlignItems::Center,
                         ..default()
                     },
-                    // background_color:
-                    // NORMAL_BUTTON.into(),
-                    image: space_sheet.sheet.clone().into(),
+                    // the nine-sliced image draws the
+                    // background
+                    background_color: Color::NONE.into(),
                     ..default()
                 },
-                TextureAtlas {
-                    index: 12,
-                    layout: space_sheet
-                        .texture_atlas_layout
-                        .clone(),
-                },
-                // ImageScaleMode::Sliced(
-                //     panel_slicer.clone(),
-                // ),
+                NineSliceImage::new(
+                    space_sheet,
+                    BUTTON_TEXTURE,
+                ),
                 TextButton,
+                Focusable,
+                self.action,
             ))
             .set_parent(self.parent)
             .with_children(|parent| {
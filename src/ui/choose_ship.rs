@@ -1,10 +1,26 @@
 use bevy::prelude::*;
 
 use crate::{
-    assets::{AudioAssets, ImageAssets},
+    assets::{AudioAssets, FontAssets, ImageAssets},
+    colors,
     kenney_assets::KenneySpriteSheetAsset,
     settings::{AudioSettings, GameSettings},
-    ship::PlayerShipType,
+    ship::{
+        PlayerEngineFire, PlayerShipType, ShipColor,
+        ENGINE_FIRE_ATLAS_INDEX,
+    },
+    ui::{
+        button::ButtonAction,
+        focus::{
+            AdjustWithArrows, FocusAdjust, FocusSet,
+            Focusable, Focused, MenuBack, MenuFocus,
+        },
+        nine_slice::NineSliceImage,
+        widgets::{
+            SELECTED_WIDGET_TEXTURE, WIDGET_TEXTURE,
+        },
+        PANEL_TEXTURE,
+    },
     GameState,
 };
 
@@ -15,13 +31,31 @@ impl Plugin for ChooseShipPlugin {
         app.add_event::<ChooseShipEvent>()
             .add_systems(
                 OnEnter(GameState::ChooseShip),
-                choose_ship_menu,
+                choose_ship_menu.run_if(not(
+                    resource_exists::<RestartWithSameShip>,
+                )),
             )
             .add_systems(
                 Update,
-                choose_ship_button_system.run_if(in_state(
-                    GameState::ChooseShip,
-                )),
+                (
+                    choose_ship_button_system
+                        .after(FocusSet),
+                    (
+                        pick_ship_color,
+                        preview_selected_ship,
+                    )
+                        .chain()
+                        .after(FocusSet),
+                    fly_ship_preview,
+                    restart_with_same_ship.run_if(
+                        resource_exists::<
+                            RestartWithSameShip,
+                        >,
+                    ),
+                )
+                    .run_if(in_state(
+                        GameState::ChooseShip,
+                    )),
             )
             .add_systems(
                 OnExit(GameState::ChooseShip),
@@ -33,15 +67,109 @@ impl Plugin for ChooseShipPlugin {
 #[derive(Event)]
 pub struct ChooseShipEvent {
     pub ship_type: PlayerShipType,
+    pub ship_color: ShipColor,
     pub ship_menu_location: Transform,
 }
 
 #[derive(Component)]
 pub struct ChooseShipMenu;
 
+/// Insert before moving to [`GameState::ChooseShip`] to
+/// skip the menu and start a new game with the ship that
+/// was picked last time.
+#[derive(Resource)]
+pub struct RestartWithSameShip;
+
+fn restart_with_same_ship(
+    mut commands: Commands,
+    ship_type: Res<PlayerShipType>,
+    ship_color: Res<ShipColor>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut choose_ship_events: EventWriter<ChooseShipEvent>,
+) {
+    commands.remove_resource::<RestartWithSameShip>();
+    choose_ship_events.send(ChooseShipEvent {
+        ship_type: ship_type.clone(),
+        ship_color: *ship_color,
+        ship_menu_location: Transform::from_xyz(0., 0., 1.),
+    });
+    next_state.set(GameState::Playing);
+}
+
 #[derive(Debug, Component)]
 pub struct ShipIndex(pub usize);
 
+/// Tints the ships and colors that aren't selected
+const UNSELECTED_SHIP: Color = Color::rgb(0.5, 0.5, 0.5);
+
+/// The speeds a full stat bar stands for
+const FULL_MOVEMENT_SPEED: f32 = 800.;
+const FULL_ROTATION_DEGREES: f32 = 540.;
+
+/// Holds the preview ship and its engine fire
+#[derive(Component)]
+struct ShipPreview;
+
+/// A ship sprite that follows the selected hull and
+/// color
+#[derive(Component)]
+enum ShipSprite {
+    /// a hull's tile, in the selected color
+    Hull(PlayerShipType),
+    /// the selected hull in the selected color
+    Preview,
+    /// a color's swatch, the selected hull's life icon in
+    /// that color
+    Swatch(ShipColor),
+}
+
+/// Picks the ship's color, left and right when focused
+#[derive(Component)]
+struct ColorPicker;
+
+/// Picks its color when clicked
+#[derive(Component)]
+struct ColorSwatch(ShipColor);
+
+/// Compared between ships on the choose ship screen.
+/// Also marks the fill of the stat's bar.
+#[derive(Component, Debug, Clone, Copy)]
+enum ShipStat {
+    Speed,
+    Handling,
+}
+
+impl ShipStat {
+    const ALL: [ShipStat; 2] =
+        [ShipStat::Speed, ShipStat::Handling];
+
+    fn label(self) -> &'static str {
+        match self {
+            ShipStat::Speed => "Speed",
+            ShipStat::Handling => "Handling",
+        }
+    }
+
+    /// How full the bar is for `ship_type`, from 0 to 1
+    fn fraction(self, ship_type: &PlayerShipType) -> f32 {
+        let speed = ship_type.base_ship_speed();
+        let fraction = match self {
+            ShipStat::Speed => {
+                speed.movement_speed / FULL_MOVEMENT_SPEED
+            }
+            ShipStat::Handling => {
+                speed.rotation_speed
+                    / FULL_ROTATION_DEGREES.to_radians()
+            }
+        };
+        fraction.clamp(0., 1.)
+    }
+
+    fn bar_width(self, ship_type: &PlayerShipType) -> Val {
+        Val::Percent(self.fraction(ship_type) * 100.)
+    }
+}
+
 pub fn hide_ship_menu(
     mut choose_ship_menu: Query<
         &mut Visibility,
@@ -55,15 +183,24 @@ pub fn hide_ship_menu(
 pub fn choose_ship_menu(
     mut commands: Commands,
     images: Res<ImageAssets>,
+    fonts: Res<FontAssets>,
+    settings: Res<GameSettings>,
     sheets: Res<Assets<KenneySpriteSheetAsset>>,
     mut choose_ship_menu: Query<
-        &mut Visibility,
+        (&mut Visibility, &mut MenuFocus),
         With<ChooseShipMenu>,
     >,
 ) {
+    // start on the ship picked last time
+    let saved_ship = PlayerShipType::all_ships()
+        .iter()
+        .position(|ship| *ship == settings.ship)
+        .unwrap_or_default();
     if !choose_ship_menu.is_empty() {
-        let mut visibility = choose_ship_menu.single_mut();
+        let (mut visibility, mut focus) =
+            choose_ship_menu.single_mut();
         *visibility = Visibility::Visible;
+        focus.0 = saved_ship;
         return;
     }
     let space_sheet =
@@ -81,11 +218,15 @@ pub fn choose_ship_menu(
                         ..default()
                     },
                     TextureAtlas {
-                        index: ship_type.base_atlas_index(),
+                        index: ship_type.atlas_index(
+                            settings.ship_color,
+                            space_sheet,
+                        ),
                         layout: space_sheet
                             .texture_atlas_layout
                             .clone(),
                     },
+                    ShipSprite::Hull(ship_type.clone()),
                 ))
                 .id();
             commands
@@ -111,44 +252,482 @@ pub fn choose_ship_menu(
                         stretch_value: 0.5,
                     },
                     ship_type,
+                    Focusable,
                 ))
                 .add_child(ship)
                 .id()
         })
         .collect();
 
-    let mut wrapper = commands.spawn((
-        NodeBundle {
+    let tiles = commands
+        .spawn(NodeBundle {
             style: Style {
-                height: Val::Percent(100.),
-                width: Val::Percent(100.),
-                justify_content: JustifyContent::Center,
-                position_type: PositionType::Absolute,
-                align_items: AlignItems::Center,
                 column_gap: Val::Px(20.),
                 ..default()
             },
             ..default()
-        },
-        ChooseShipMenu,
-    ));
+        })
+        .push_children(&ships)
+        .id();
 
-    for ship in ships {
-        wrapper.add_child(ship);
+    let color_picker = spawn_color_picker(
+        &mut commands,
+        &images,
+        space_sheet,
+        &settings,
+    );
+    let preview = spawn_ship_preview(
+        &mut commands,
+        space_sheet,
+        &images,
+        &settings,
+    );
+    let stats = spawn_ship_stats(
+        &mut commands,
+        &images,
+        &fonts,
+        &settings.ship,
+    );
+    let details = commands
+        .spawn(NodeBundle {
+            style: Style {
+                column_gap: Val::Px(20.),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .push_children(&[preview, stats])
+        .id();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    height: Val::Percent(100.),
+                    width: Val::Percent(100.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    position_type: PositionType::Absolute,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.),
+                    ..default()
+                },
+                ..default()
+            },
+            ChooseShipMenu,
+            MenuFocus(saved_ship),
+            MenuBack(ButtonAction::ChangeState(
+                GameState::Menu,
+            )),
+        ))
+        .push_children(&[tiles, color_picker, details]);
+}
+
+/// The selected ship, flying in place with its engine
+/// lit
+fn spawn_ship_preview(
+    commands: &mut Commands,
+    space_sheet: &KenneySpriteSheetAsset,
+    images: &ImageAssets,
+    settings: &GameSettings,
+) -> Entity {
+    let layout = space_sheet.texture_atlas_layout.clone();
+    let ship = commands
+        .spawn((
+            ImageBundle {
+                image: space_sheet.sheet.clone().into(),
+                ..default()
+            },
+            TextureAtlas {
+                index: settings.ship.atlas_index(
+                    settings.ship_color,
+                    space_sheet,
+                ),
+                layout: layout.clone(),
+            },
+            ShipSprite::Preview,
+        ))
+        .id();
+    let engine_fire = commands
+        .spawn((
+            ImageBundle {
+                image: UiImage {
+                    texture: space_sheet.sheet.clone(),
+                    flip_y: true,
+                    ..default()
+                },
+                style: Style {
+                    // tuck the flame under the ship
+                    margin: UiRect::top(Val::Px(-10.)),
+                    ..default()
+                },
+                ..default()
+            },
+            TextureAtlas {
+                index: ENGINE_FIRE_ATLAS_INDEX,
+                layout,
+            },
+            PlayerEngineFire::animation(space_sheet),
+        ))
+        .id();
+    let flying_ship = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            ShipPreview,
+        ))
+        .push_children(&[ship, engine_fire])
+        .id();
+    commands
+        .spawn((
+            ImageBundle {
+                style: Style {
+                    width: Val::Px(200.0),
+                    height: Val::Px(200.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                image: images
+                    .pattern_blueprint
+                    .clone()
+                    .into(),
+                ..default()
+            },
+            ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: true,
+                stretch_value: 0.5,
+            },
+        ))
+        .add_child(flying_ship)
+        .id()
+}
+
+/// A swatch for each [`ShipColor`], showing the selected
+/// hull's life icon in that color
+fn spawn_color_picker(
+    commands: &mut Commands,
+    images: &ImageAssets,
+    space_sheet: &KenneySpriteSheetAsset,
+    settings: &GameSettings,
+) -> Entity {
+    let swatches: Vec<_> = ShipColor::ALL
+        .into_iter()
+        .map(|color| {
+            let icon = commands
+                .spawn((
+                    ImageBundle {
+                        image: space_sheet
+                            .sheet
+                            .clone()
+                            .into(),
+                        ..default()
+                    },
+                    TextureAtlas {
+                        index: settings
+                            .ship
+                            .life_atlas_index(
+                                color,
+                                space_sheet,
+                            ),
+                        layout: space_sheet
+                            .texture_atlas_layout
+                            .clone(),
+                    },
+                    ShipSprite::Swatch(color),
+                ))
+                .id();
+            commands
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(60.0),
+                            height: Val::Px(50.0),
+                            justify_content:
+                                JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        image: images
+                            .pattern_blueprint
+                            .clone()
+                            .into(),
+                        ..default()
+                    },
+                    ImageScaleMode::Tiled {
+                        tile_x: true,
+                        tile_y: true,
+                        stretch_value: 0.5,
+                    },
+                    ColorSwatch(color),
+                ))
+                .add_child(icon)
+                .id()
+        })
+        .collect();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    column_gap: Val::Px(10.),
+                    padding: UiRect::all(Val::Px(6.)),
+                    ..default()
+                },
+                ..default()
+            },
+            ColorPicker,
+            Focusable,
+            AdjustWithArrows,
+        ))
+        .push_children(&swatches)
+        .id()
+}
+
+/// A panel with a bar for each [`ShipStat`] of the
+/// selected ship
+fn spawn_ship_stats(
+    commands: &mut Commands,
+    images: &ImageAssets,
+    fonts: &FontAssets,
+    ship_type: &PlayerShipType,
+) -> Entity {
+    let text_style = TextStyle {
+        font: fonts.roboto.clone(),
+        font_size: 25.0,
+        color: colors::TEXT,
+    };
+    let panel = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(400.0),
+                    height: Val::Px(200.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content:
+                        JustifyContent::SpaceEvenly,
+                    padding: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            NineSliceImage::new(
+                images.ui_sheet.clone(),
+                PANEL_TEXTURE,
+            ),
+        ))
+        .id();
+    for stat in ShipStat::ALL {
+        let label = commands
+            .spawn(TextBundle::from_section(
+                stat.label(),
+                text_style.clone(),
+            ))
+            .id();
+        let fill = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: stat.bar_width(ship_type),
+                        height: Val::Percent(100.0),
+                        // the slices' corners
+                        min_width: Val::Px(12.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                NineSliceImage::new(
+                    images.ui_sheet.clone(),
+                    SELECTED_WIDGET_TEXTURE,
+                ),
+                stat,
+            ))
+            .id();
+        let bar = commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        width: Val::Px(200.0),
+                        height: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                NineSliceImage::new(
+                    images.ui_sheet.clone(),
+                    WIDGET_TEXTURE,
+                ),
+            ))
+            .add_child(fill)
+            .id();
+        let row = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    justify_content:
+                        JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .push_children(&[label, bar])
+            .id();
+        commands.entity(panel).add_child(row);
     }
+    panel
 }
 
-pub fn choose_ship_button_system(
-    mut commands: Commands,
-    mut interaction_query: Query<
+/// Left and right on the focused [`ColorPicker`], or
+/// clicking a [`ColorSwatch`], picks the ship's color
+fn pick_ship_color(
+    mut adjust: EventReader<FocusAdjust>,
+    pickers: Query<(), With<ColorPicker>>,
+    swatches: Query<
+        (&Interaction, &ColorSwatch),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<GameSettings>,
+) {
+    let mut color = settings.ship_color;
+    let count = ShipColor::ALL.len();
+    for event in adjust.read() {
+        if !pickers.contains(event.entity) {
+            continue;
+        }
+        let index = color as usize;
+        color = ShipColor::ALL[if event.direction > 0. {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        }];
+    }
+    for (interaction, swatch) in &swatches {
+        if *interaction == Interaction::Pressed {
+            color = swatch.0;
+        }
+    }
+    if settings.ship_color != color {
+        settings.ship_color = color;
+    }
+}
+
+/// Shows the selected hull and color in the ship
+/// sprites and stat bars, and dims the hulls and colors
+/// that aren't selected. The selected hull is the one
+/// focused last.
+#[allow(clippy::too_many_arguments)]
+fn preview_selected_ship(
+    images: Res<ImageAssets>,
+    sheets: Res<Assets<KenneySpriteSheetAsset>>,
+    settings: Res<GameSettings>,
+    mut shown: Local<Option<(PlayerShipType, ShipColor)>>,
+    mut ships: Query<
         (
-            &Interaction,
             &PlayerShipType,
-            &Transform,
+            Has<Focused>,
+            &mut BackgroundColor,
         ),
-        Changed<Interaction>,
+        With<Focusable>,
+    >,
+    mut swatches: Query<
+        (&ColorSwatch, &mut BackgroundColor),
+        Without<PlayerShipType>,
     >,
+    mut sprites: Query<(&ShipSprite, &mut TextureAtlas)>,
+    mut bars: Query<(&ShipStat, &mut Style)>,
+) {
+    let Some(space_sheet) = sheets.get(&images.space_sheet)
+    else {
+        warn!("preview_selected_ship requires the space sheet to be loaded");
+        return;
+    };
+    let hull = ships
+        .iter()
+        .find(|(_, focused, _)| *focused)
+        .map(|(ship_type, ..)| ship_type.clone())
+        .or_else(|| {
+            shown.as_ref().map(|(hull, _)| hull.clone())
+        })
+        .unwrap_or_else(|| settings.ship.clone());
+    let color = settings.ship_color;
+    if shown.as_ref() == Some(&(hull.clone(), color)) {
+        return;
+    }
+
+    for (ship_type, _, mut background) in &mut ships {
+        let tint = if *ship_type == hull {
+            Color::WHITE
+        } else {
+            UNSELECTED_SHIP
+        };
+        if background.0 != tint {
+            background.0 = tint;
+        }
+    }
+    for (swatch, mut background) in &mut swatches {
+        background.0 = if swatch.0 == color {
+            Color::WHITE
+        } else {
+            UNSELECTED_SHIP
+        };
+    }
+    for (sprite, mut atlas) in &mut sprites {
+        atlas.index = match sprite {
+            ShipSprite::Hull(ship_type) => {
+                ship_type.atlas_index(color, space_sheet)
+            }
+            ShipSprite::Preview => {
+                hull.atlas_index(color, space_sheet)
+            }
+            ShipSprite::Swatch(swatch_color) => hull
+                .life_atlas_index(
+                    *swatch_color,
+                    space_sheet,
+                ),
+        };
+    }
+    for (stat, mut style) in &mut bars {
+        style.width = stat.bar_width(&hull);
+    }
+    *shown = Some((hull, color));
+}
+
+/// Bobs and sways the preview ship, unless
+/// [`GameSettings::reduce_motion`] is on
+fn fly_ship_preview(
+    time: Res<Time>,
     settings: Res<GameSettings>,
+    mut preview: Query<
+        (&mut Style, &mut Transform),
+        With<ShipPreview>,
+    >,
+) {
+    let t = time.elapsed_seconds();
+    let (offset, angle) = if settings.reduce_motion {
+        (0., 0.)
+    } else {
+        ((t * 2.).sin() * 8., (t * 1.3).sin() * 0.1)
+    };
+    for (mut style, mut transform) in &mut preview {
+        style.top = Val::Px(offset);
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+pub fn choose_ship_button_system(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &PlayerShipType, &Transform),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<GameSettings>,
     sounds: Res<AudioAssets>,
     mut next_state: ResMut<NextState<GameState>>,
     mut choose_ship_events: EventWriter<ChooseShipEvent>,
@@ -167,9 +746,13 @@ pub fn choose_ship_button_system(
                     // });
                 }
                 // *color = PRESSED_BUTTON.into();
+                if settings.ship != *ship_type {
+                    settings.ship = ship_type.clone();
+                }
 
                 choose_ship_events.send(ChooseShipEvent {
                     ship_type: ship_type.clone(),
+                    ship_color: settings.ship_color,
                     ship_menu_location: *transform,
                 });
                 next_state.set(GameState::Playing);
@@ -178,7 +761,7 @@ pub fn choose_ship_button_system(
                 if settings.audio == AudioSettings::ON {
                     commands.spawn(AudioBundle {
                         source: sounds.menu_click.clone(),
-                        ..default()
+                        settings: settings.sound_effect(),
                     });
                 }
                 // *color = HOVERED_BUTTON.into();
@@ -0,0 +1,288 @@
+//! Keyboard and gamepad navigation for every menu.
+//!
+//! A panel with [`MenuFocus`] keeps track of which of its
+//! [`Focusable`] descendants is focused, and outlines it.
+//! Only one panel is navigated at a time, the topmost
+//! visible one in the [`UiStack`], so a dialog takes
+//! over from the panel it covers.
+//!
+//! - up/down (arrows, W/S or the d-pad) move the focus,
+//!   as do left/right unless the focused item takes them
+//!   as a [`FocusAdjust`], see [`AdjustWithArrows`]
+//! - Enter or the south button presses the focused item,
+//!   like a click would
+//! - Escape or the east button runs the panel's
+//!   [`MenuBack`] action
+//!
+//! Hovering an item with the mouse focuses it too, so
+//! there's only ever one highlighted item. Systems
+//! reacting to [`Interaction`] should run after
+//! [`FocusSet`] to see presses made here.
+
+use bevy::{prelude::*, ui::UiStack};
+
+use super::button::{ButtonAction, ButtonActions};
+use crate::{colors, GameState};
+
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<FocusAdjust>()
+            .configure_sets(
+                Update,
+                FocusSet.run_if(not(in_state(
+                    GameState::AssetLoading,
+                ))),
+            )
+            .add_systems(
+                Update,
+                navigate_menus.in_set(FocusSet),
+            );
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FocusSet;
+
+/// A panel whose [`Focusable`] descendants can be
+/// focused with the keyboard or a gamepad, along with
+/// the index of the focused one
+#[derive(Component, Debug, Default)]
+pub struct MenuFocus(pub usize);
+
+/// What Escape, or a gamepad's east button, does in a
+/// [`MenuFocus`] panel
+#[derive(Component, Debug)]
+pub struct MenuBack(pub ButtonAction);
+
+/// Something in a menu that can be focused. Pressing
+/// Enter on it sets its [`Interaction`] to
+/// [`Interaction::Pressed`] for a frame, if it has one.
+#[derive(Component, Debug)]
+pub struct Focusable;
+
+/// A [`Focusable`] that left and right adjust, like a
+/// slider, rather than moving the focus away from it
+#[derive(Component, Debug)]
+pub struct AdjustWithArrows;
+
+/// Left or right was pressed on a focused
+/// [`AdjustWithArrows`] item
+#[derive(Event, Debug)]
+pub struct FocusAdjust {
+    pub entity: Entity,
+    /// -1 for left, 1 for right
+    pub direction: f32,
+}
+
+/// Marks the focused item, which is also outlined
+#[derive(Component, Debug)]
+pub struct Focused;
+
+const FOCUS_OUTLINE_WIDTH: Val = Val::Px(3.0);
+
+/// The visible [`Focusable`] descendants of `entity`, in
+/// the order they are laid out. Panels nested inside
+/// are navigated on their own.
+#[allow(clippy::type_complexity)]
+fn collect_focusables(
+    entity: Entity,
+    children: &Query<&Children>,
+    nodes: &Query<(
+        Option<&Visibility>,
+        Option<&Style>,
+        Has<MenuFocus>,
+        Has<Focusable>,
+    )>,
+    focusables: &mut Vec<Entity>,
+) {
+    let Ok(entity_children) = children.get(entity) else {
+        return;
+    };
+    for child in entity_children {
+        let Ok((visibility, style, is_panel, is_focusable)) =
+            nodes.get(*child)
+        else {
+            continue;
+        };
+        if is_panel
+            || visibility == Some(&Visibility::Hidden)
+            || style.is_some_and(|style| {
+                style.display == Display::None
+            })
+        {
+            continue;
+        }
+        if is_focusable {
+            focusables.push(*child);
+        }
+        collect_focusables(
+            *child, children, nodes, focusables,
+        );
+    }
+}
+
+#[allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity
+)]
+fn navigate_menus(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    ui_stack: Res<UiStack>,
+    mut panels: Query<(
+        Entity,
+        &mut MenuFocus,
+        &InheritedVisibility,
+        Option<&MenuBack>,
+    )>,
+    children: Query<&Children>,
+    nodes: Query<(
+        Option<&Visibility>,
+        Option<&Style>,
+        Has<MenuFocus>,
+        Has<Focusable>,
+    )>,
+    mut interactions: Query<
+        &mut Interaction,
+        With<Focusable>,
+    >,
+    adjustable: Query<(), With<AdjustWithArrows>>,
+    focused: Query<Entity, With<Focused>>,
+    mut adjust: EventWriter<FocusAdjust>,
+    mut actions: ButtonActions,
+) {
+    let gamepad_pressed = |button_type| {
+        gamepads.iter().any(|gamepad| {
+            gamepad_input.just_pressed(GamepadButton::new(
+                gamepad,
+                button_type,
+            ))
+        })
+    };
+    let up = input.just_pressed(KeyCode::ArrowUp)
+        || input.just_pressed(KeyCode::KeyW)
+        || gamepad_pressed(GamepadButtonType::DPadUp);
+    let down = input.just_pressed(KeyCode::ArrowDown)
+        || input.just_pressed(KeyCode::KeyS)
+        || gamepad_pressed(GamepadButtonType::DPadDown);
+    let left = input.just_pressed(KeyCode::ArrowLeft)
+        || input.just_pressed(KeyCode::KeyA)
+        || gamepad_pressed(GamepadButtonType::DPadLeft);
+    let right = input.just_pressed(KeyCode::ArrowRight)
+        || input.just_pressed(KeyCode::KeyD)
+        || gamepad_pressed(GamepadButtonType::DPadRight);
+    let activate = input.just_pressed(KeyCode::Enter)
+        || gamepad_pressed(GamepadButtonType::South);
+    let back = input.just_pressed(KeyCode::Escape)
+        || gamepad_pressed(GamepadButtonType::East);
+
+    // the ui focus system only releases items pressed
+    // with the mouse
+    if !mouse_input.pressed(MouseButton::Left)
+        && !mouse_input.just_released(MouseButton::Left)
+    {
+        for mut interaction in &mut interactions {
+            if *interaction == Interaction::Pressed {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+
+    // the stack is ordered back to front
+    let Some(panel) = ui_stack
+        .uinodes
+        .iter()
+        .rev()
+        .copied()
+        .find(|entity| {
+            panels.get(*entity).is_ok_and(
+                |(_, _, visibility, _)| visibility.get(),
+            )
+        })
+    else {
+        return;
+    };
+    let Ok((_, mut focus, _, menu_back)) =
+        panels.get_mut(panel)
+    else {
+        return;
+    };
+
+    let mut items = vec![];
+    collect_focusables(
+        panel, &children, &nodes, &mut items,
+    );
+    if items.is_empty() {
+        // items are mostly spawned by commands, so they
+        // show up a frame after the panel
+        return;
+    }
+
+    let count = items.len();
+    let current = focus.0.min(count - 1);
+    let adjusting = (left || right)
+        && adjustable.contains(items[current]);
+    let hovered = items.iter().position(|item| {
+        interactions.get_mut(*item).is_ok_and(
+            |interaction| {
+                interaction.is_changed()
+                    && *interaction == Interaction::Hovered
+            },
+        )
+    });
+    let target = if up || (left && !adjusting) {
+        (current + count - 1) % count
+    } else if down || (right && !adjusting) {
+        (current + 1) % count
+    } else {
+        hovered.unwrap_or(current)
+    };
+    if focus.0 != target {
+        focus.0 = target;
+    }
+
+    let target = items[target];
+    for entity in &focused {
+        if entity != target {
+            commands
+                .entity(entity)
+                .remove::<(Focused, Outline)>();
+        }
+    }
+    if !focused.contains(target) {
+        // the menu may be closing this frame
+        commands.entity(target).try_insert((
+            Focused,
+            Outline::new(
+                FOCUS_OUTLINE_WIDTH,
+                Val::Px(0.0),
+                colors::FOCUS,
+            ),
+        ));
+    }
+
+    if adjusting {
+        adjust.send(FocusAdjust {
+            entity: target,
+            direction: if right { 1. } else { -1. },
+        });
+    }
+    if activate {
+        if let Ok(mut interaction) =
+            interactions.get_mut(target)
+        {
+            // released again next frame, like a click
+            *interaction = Interaction::Pressed;
+        }
+    }
+    if back {
+        if let Some(MenuBack(action)) = menu_back {
+            actions.run(action);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+
+use crate::{
+    assets::{FontAssets, ImageAssets},
+    colors,
+    scores::Scores,
+    stats::{format_duration, BestTime, RunStats},
+    ui::{
+        button::{ButtonAction, SpawnButton},
+        focus::{MenuBack, MenuFocus},
+        nine_slice::NineSliceImage,
+        PANEL_TEXTURE,
+    },
+    GameState,
+};
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            show_run_summary,
+        )
+        .add_systems(
+            OnExit(GameState::GameOver),
+            remove_run_summary,
+        );
+    }
+}
+
+#[derive(Component)]
+struct RunSummary;
+
+fn show_run_summary(
+    mut commands: Commands,
+    images: Res<ImageAssets>,
+    fonts: Res<FontAssets>,
+    stats: Res<RunStats>,
+    best_time: Res<BestTime>,
+    scores: Res<Scores>,
+) {
+    let heading_style = TextStyle {
+        font: fonts.alfa_slab_one_regular.clone(),
+        font_size: 40.0,
+        color: colors::TEXT,
+    };
+    let label_style = TextStyle {
+        font: fonts.roboto.clone(),
+        font_size: 22.0,
+        color: colors::TEXT,
+    };
+
+    let meteors = stats.meteors_destroyed;
+    let mut rows = vec![
+        ("Score", scores.current.to_string()),
+        ("Level reached", stats.level_reached.to_string()),
+        (
+            "Time alive",
+            format!(
+                "{} (best {})",
+                format_duration(stats.time_alive()),
+                format_duration(**best_time)
+            ),
+        ),
+        ("Shots fired", stats.shots_fired.to_string()),
+        ("Hits", stats.hits.to_string()),
+        (
+            "Accuracy",
+            format!("{:.0}%", stats.accuracy() * 100.),
+        ),
+        (
+            "Meteors",
+            format!(
+                "{} ({} big, {} medium, {} small)",
+                meteors.total(),
+                meteors.big,
+                meteors.medium,
+                meteors.small
+            ),
+        ),
+        ("UFOs", stats.ufos_destroyed.to_string()),
+        (
+            "Meteors shot by UFOs",
+            stats.meteors_destroyed_by_ufos.to_string(),
+        ),
+    ];
+    let life_labels: Vec<String> = (1..=stats.lives.len())
+        .map(|life| format!("Life {life}"))
+        .collect();
+    rows.extend(life_labels.iter().zip(&stats.lives).map(
+        |(label, duration)| {
+            (label.as_str(), format_duration(*duration))
+        },
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    height: Val::Percent(100.),
+                    width: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                ..default()
+            },
+            RunSummary,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(420.0),
+                            flex_direction:
+                                FlexDirection::Column,
+                            row_gap: Val::Px(6.0),
+                            padding: UiRect::all(Val::Px(
+                                20.0,
+                            )),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    NineSliceImage::new(
+                        images.ui_sheet.clone(),
+                        PANEL_TEXTURE,
+                    ),
+                    MenuFocus::default(),
+                    MenuBack(ButtonAction::ChangeState(
+                        GameState::Menu,
+                    )),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(
+                        TextBundle::from_section(
+                            "Game Over",
+                            heading_style,
+                        )
+                        .with_style(Style {
+                            align_self: AlignSelf::Center,
+                            margin: UiRect::bottom(
+                                Val::Px(10.0),
+                            ),
+                            ..default()
+                        }),
+                    );
+                    for (label, value) in rows {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    justify_content:
+                                        JustifyContent::SpaceBetween,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn(
+                                    TextBundle::from_section(
+                                        label,
+                                        label_style.clone(),
+                                    ),
+                                );
+                                parent.spawn(
+                                    TextBundle::from_section(
+                                        value,
+                                        label_style.clone(),
+                                    ),
+                                );
+                            });
+                    }
+                    let entity = parent.parent_entity();
+                    parent.add_command(SpawnButton {
+                        parent: entity,
+                        text: "Play Again",
+                        action: ButtonAction::ChangeState(
+                            GameState::ChooseShip,
+                        ),
+                    });
+                    parent.add_command(SpawnButton {
+                        parent: entity,
+                        text: "Main Menu",
+                        action: ButtonAction::ChangeState(
+                            GameState::Menu,
+                        ),
+                    });
+                });
+        });
+}
+
+fn remove_run_summary(
+    mut commands: Commands,
+    summary: Query<Entity, With<RunSummary>>,
+) {
+    for entity in &summary {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,156 @@
+//! The main menu's pages form a stack, only the top one
+//! is shown. Buttons open pages on top with
+//! [`PushPage`](ButtonAction::PushPage) and go back with
+//! [`PopPage`](ButtonAction::PopPage), which pages use as
+//! their [`MenuBack`](super::focus::MenuBack) so Escape
+//! goes back too.
+//!
+//! Every page keeps its own
+//! [`MenuFocus`](super::focus::MenuFocus), so going back
+//! lands on the item that opened the page.
+//!
+//! Pages grow into place when pushed, and shrink into
+//! place when uncovered again, unless
+//! [`GameSettings::reduce_motion`] is on.
+
+use bevy::prelude::*;
+
+#[cfg(doc)]
+use super::button::ButtonAction;
+use super::MenuPage;
+use crate::{settings::GameSettings, GameState};
+
+pub struct MenuStackPlugin;
+
+impl Plugin for MenuStackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuStack>().add_systems(
+            Update,
+            (show_current_page, animate_page_transitions)
+                .chain()
+                .run_if(not(in_state(
+                    GameState::AssetLoading,
+                ))),
+        );
+    }
+}
+
+/// How long a page takes to settle into place
+const TRANSITION_SECONDS: f32 = 0.2;
+
+/// The open pages of the main menu, with the main page
+/// always at the bottom
+#[derive(Resource, Debug)]
+pub struct MenuStack(Vec<MenuPage>);
+
+impl Default for MenuStack {
+    fn default() -> Self {
+        MenuStack(vec![MenuPage::Main])
+    }
+}
+
+impl MenuStack {
+    /// The page that is shown
+    pub fn current(&self) -> MenuPage {
+        self.0.last().copied().unwrap_or(MenuPage::Main)
+    }
+
+    /// How many pages are open, including the main page
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Open `page` on top, unless it's already shown
+    pub fn push(&mut self, page: MenuPage) {
+        if self.current() != page {
+            self.0.push(page);
+        }
+    }
+
+    /// Go back a page. The main page is never popped.
+    pub fn pop(&mut self) -> Option<MenuPage> {
+        if self.0.len() > 1 {
+            self.0.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Close every page but the main page
+    pub fn clear(&mut self) {
+        self.0.truncate(1);
+    }
+}
+
+/// Scales a page from `from` to its full size as it is
+/// shown
+#[derive(Component)]
+struct PageTransition {
+    timer: Timer,
+    from: f32,
+}
+
+fn show_current_page(
+    mut commands: Commands,
+    stack: Res<MenuStack>,
+    settings: Res<GameSettings>,
+    mut previous_depth: Local<usize>,
+    mut pages: Query<(Entity, &MenuPage, &mut Visibility)>,
+) {
+    if !stack.is_changed() {
+        return;
+    }
+    let pushed = stack.depth() >= *previous_depth;
+    *previous_depth = stack.depth();
+
+    let current = stack.current();
+    for (entity, page, mut visibility) in &mut pages {
+        if *page != current {
+            *visibility = Visibility::Hidden;
+        } else if *visibility == Visibility::Hidden {
+            *visibility = Visibility::Inherited;
+            if settings.reduce_motion {
+                continue;
+            }
+            commands.entity(entity).insert(
+                PageTransition {
+                    timer: Timer::from_seconds(
+                        TRANSITION_SECONDS,
+                        TimerMode::Once,
+                    ),
+                    from: if pushed { 0.9 } else { 1.1 },
+                },
+            );
+        }
+    }
+}
+
+/// Runs on real time, so pages still animate while the
+/// game is paused
+fn animate_page_transitions(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut pages: Query<(
+        Entity,
+        &mut PageTransition,
+        &mut Transform,
+    )>,
+) {
+    for (entity, mut transition, mut transform) in
+        &mut pages
+    {
+        transition.timer.tick(time.delta());
+        // ease out, fast at first and settling gently
+        let t =
+            1. - (1. - transition.timer.fraction()).powi(3);
+        transform.scale = Vec3::splat(
+            transition.from + (1. - transition.from) * t,
+        );
+        if transition.timer.finished() {
+            transform.scale = Vec3::ONE;
+            commands
+                .entity(entity)
+                .remove::<PageTransition>();
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! `ImageScaleMode::Sliced` can't slice a texture atlas
+//! entry, so UI built from the spritesheets gets its own
+//! nine-slicing: the sheet loader adds a rect for each
+//! of the nine pieces of a sub-texture with slice
+//! borders, and [`NineSliceImage`] lays those pieces out
+//! behind a node's content.
+
+use bevy::prelude::*;
+
+use crate::kenney_assets::KenneySpriteSheetAsset;
+
+pub struct NineSlicePlugin;
+
+impl Plugin for NineSlicePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (update_nine_slices, build_nine_slices).chain(),
+        );
+    }
+}
+
+/// Draw the nine-sliced sub-texture `name` of `sheet`
+/// behind this node's children.
+///
+/// The node itself should have no background, use
+/// `color` to tint the slices instead.
+#[derive(Component, Debug, Clone)]
+pub struct NineSliceImage {
+    pub sheet: Handle<KenneySpriteSheetAsset>,
+    pub name: String,
+    pub color: Color,
+}
+
+impl NineSliceImage {
+    pub fn new(
+        sheet: Handle<KenneySpriteSheetAsset>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            sheet,
+            name: name.into(),
+            color: Color::WHITE,
+        }
+    }
+}
+
+/// The node holding the nine pieces of a
+/// [`NineSliceImage`]
+#[derive(Component)]
+struct NineSliceBuilt {
+    background: Entity,
+    name: String,
+}
+
+fn build_nine_slices(
+    mut commands: Commands,
+    sheets: Res<Assets<KenneySpriteSheetAsset>>,
+    images: Query<
+        (Entity, &NineSliceImage),
+        Without<NineSliceBuilt>,
+    >,
+) {
+    for (entity, image) in &images {
+        let Some(sheet) = sheets.get(&image.sheet) else {
+            // try again once the sheet has loaded
+            continue;
+        };
+        let Some(nine_slice) =
+            sheet.nine_slice(&image.name)
+        else {
+            error_once!(
+                "{:?} has no slice borders, add them to the sheet's xml or .slices.json",
+                image.name
+            );
+            continue;
+        };
+
+        let border = nine_slice.border;
+        // left, width, right of each column and top,
+        // height, bottom of each row. `None` stretches
+        // between the two insets.
+        let columns = [
+            (Val::Px(0.), Some(border.left), Val::Auto),
+            (
+                Val::Px(border.left),
+                None,
+                Val::Px(border.right),
+            ),
+            (Val::Auto, Some(border.right), Val::Px(0.)),
+        ];
+        let rows = [
+            (Val::Px(0.), Some(border.top), Val::Auto),
+            (
+                Val::Px(border.top),
+                None,
+                Val::Px(border.bottom),
+            ),
+            (Val::Auto, Some(border.bottom), Val::Px(0.)),
+        ];
+
+        let background = commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(0.),
+                    right: Val::Px(0.),
+                    top: Val::Px(0.),
+                    bottom: Val::Px(0.),
+                    ..default()
+                },
+                z_index: ZIndex::Local(-1),
+                ..default()
+            })
+            .with_children(|parent| {
+                for (piece, atlas_index) in
+                    nine_slice.pieces.iter().enumerate()
+                {
+                    let (left, width, right) =
+                        columns[piece % 3];
+                    let (top, height, bottom) =
+                        rows[piece / 3];
+                    parent.spawn((
+                        ImageBundle {
+                            style: Style {
+                                position_type:
+                                    PositionType::Absolute,
+                                left,
+                                right,
+                                top,
+                                bottom,
+                                width: width.map_or(
+                                    Val::Auto,
+                                    Val::Px,
+                                ),
+                                height: height.map_or(
+                                    Val::Auto,
+                                    Val::Px,
+                                ),
+                                ..default()
+                            },
+                            image: sheet
+                                .sheet
+                                .clone()
+                                .into(),
+                            background_color: image
+                                .color
+                                .into(),
+                            ..default()
+                        },
+                        TextureAtlas {
+                            index: *atlas_index,
+                            layout: sheet
+                                .texture_atlas_layout
+                                .clone(),
+                        },
+                    ));
+                }
+            })
+            .id();
+
+        commands
+            .entity(entity)
+            .insert(NineSliceBuilt {
+                background,
+                name: image.name.clone(),
+            })
+            .add_child(background);
+    }
+}
+
+/// Re-tint the pieces when the color changes, and
+/// rebuild them when the sub-texture does.
+fn update_nine_slices(
+    mut commands: Commands,
+    images: Query<
+        (Entity, &NineSliceImage, &NineSliceBuilt),
+        Changed<NineSliceImage>,
+    >,
+    backgrounds: Query<&Children>,
+    mut pieces: Query<&mut BackgroundColor>,
+) {
+    for (entity, image, built) in &images {
+        if image.name != built.name {
+            commands
+                .entity(built.background)
+                .despawn_recursive();
+            commands
+                .entity(entity)
+                .remove::<NineSliceBuilt>();
+            continue;
+        }
+        let Ok(children) =
+            backgrounds.get(built.background)
+        else {
+            continue;
+        };
+        for child in children {
+            if let Ok(mut color) = pieces.get_mut(*child) {
+                *color = image.color.into();
+            }
+        }
+    }
+}
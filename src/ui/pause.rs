@@ -1,16 +1,75 @@
-use bevy::prelude::*;
+//! Pausing stops the virtual clock (and with it the
+//! physics clock), so everything driven by [`Time`]
+//! freezes in place: movement, timers, animations, the
+//! fixed timestep and cooldowns measured with
+//! `time.elapsed()`. Resuming picks up where it left off
+//! without a jump in the delta.
+//!
+//! [`Pausable`] mirrors the virtual clock for systems
+//! that use it as a run condition.
+//!
+//! While paused, the pause menu offers Resume, Restart,
+//! Settings and Quit to Menu, navigable with the
+//! keyboard or a gamepad like every menu (see
+//! [`super::focus`]). Restarting and quitting ask for
+//! confirmation first.
+//!
+//! The game also pauses itself when the window loses
+//! focus or is minimized, unless
+//! [`GameSettings::pause_on_focus_loss`] is off.
 
-use crate::{assets::ImageAssets, GameState};
+use bevy::{
+    ecs::system::SystemId,
+    prelude::*,
+    window::{
+        WindowFocused, WindowOccluded, WindowResized,
+    },
+};
+use bevy_xpbd_2d::prelude::{Physics, PhysicsTime};
+
+use crate::{
+    assets::ImageAssets,
+    settings::GameSettings,
+    ui::{
+        button::{ButtonAction, SpawnButton},
+        choose_ship::RestartWithSameShip,
+        focus::{FocusSet, MenuBack, MenuFocus},
+        menu_stack::MenuStack,
+        nine_slice::NineSliceImage,
+        MenuPage, PANEL_TEXTURE,
+    },
+    GameState,
+};
 
 pub struct PausePlugin;
 
 impl Plugin for PausePlugin {
     fn build(&self, app: &mut App) {
+        let actions = PauseMenuActions {
+            resume: app.world.register_system(unpause),
+            confirm_restart: app
+                .world
+                .register_system(confirm_restart),
+            confirm_quit: app
+                .world
+                .register_system(confirm_quit),
+            restart: app.world.register_system(restart_run),
+            cancel: app
+                .world
+                .register_system(close_confirm_dialog),
+        };
+        app.insert_resource(actions);
         app.add_systems(
             Update,
-            handle_pause_toggle
+            (
+                handle_pause_toggle.before(FocusSet),
+                pause_on_focus_loss,
+            )
                 .run_if(in_state(GameState::Playing)),
         )
+        .add_systems(PreUpdate, sync_pausable)
+        // never leave the clock stopped outside of a game
+        .add_systems(OnExit(GameState::Playing), unpause)
         .add_systems(
             Update,
             (
@@ -25,13 +84,19 @@ impl Plugin for PausePlugin {
                         Pausable::NotPaused,
                     ))
                     .run_if(resource_exists::<PauseMenu>),
+                hide_pause_panel_for_settings
+                    .run_if(resource_exists::<PauseMenu>),
             ),
         )
         .insert_resource(Pausable::NotPaused);
     }
 }
 
-#[derive(Resource, PartialEq, Eq)]
+/// Whether the game is paused, as of the start of the
+/// frame. Pause and resume with [`pause`] and
+/// [`unpause`] (or by pausing [`Time<Virtual>`]) rather
+/// than by setting this.
+#[derive(Resource, PartialEq, Eq, Debug)]
 pub enum Pausable {
     Paused,
     NotPaused,
@@ -40,18 +105,97 @@ pub enum Pausable {
 #[derive(Resource)]
 struct PauseMenu(Entity);
 
+/// The panel with the Resume/Restart/Settings/Quit
+/// buttons
+#[derive(Component)]
+struct PausePanel;
+
+/// Asks before doing something that throws away the
+/// current run
+#[derive(Component, Clone, Copy)]
+enum ConfirmDialog {
+    Restart,
+    QuitToMenu,
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_pause_toggle(
     input: Res<ButtonInput<KeyCode>>,
-    mut pausable: ResMut<Pausable>,
+    settings: Res<GameSettings>,
+    menu_stack: Res<MenuStack>,
+    dialogs: Query<(), With<ConfirmDialog>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<ButtonInput<GamepadButton>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
 ) {
-    if input.just_pressed(KeyCode::Enter) {
-        *pausable = match *pausable {
-            Pausable::Paused => Pausable::NotPaused,
-            Pausable::NotPaused => Pausable::Paused,
+    let start_pressed = gamepads.iter().any(|gamepad| {
+        gamepad_input.just_pressed(GamepadButton::new(
+            gamepad,
+            GamepadButtonType::Start,
+        ))
+    });
+    if time.is_paused() {
+        // Enter picks the focused pause menu button
+        // instead, which starts out as Resume. Dialogs
+        // and menu pages are closed first.
+        let on_pause_panel = menu_stack.current()
+            == MenuPage::Main
+            && dialogs.is_empty();
+        if on_pause_panel
+            && (input.just_pressed(settings.controls.pause)
+                || start_pressed)
+        {
+            time.unpause();
+            physics_time.unpause();
         }
+    } else if input.just_pressed(KeyCode::Enter)
+        || input.just_pressed(settings.controls.pause)
+        || start_pressed
+    {
+        time.pause();
+        physics_time.pause();
+    }
+}
+
+fn pause_on_focus_loss(
+    settings: Res<GameSettings>,
+    mut focused: EventReader<WindowFocused>,
+    mut occluded: EventReader<WindowOccluded>,
+    mut resized: EventReader<WindowResized>,
+    mut time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    let focus_lost =
+        focused.read().any(|event| !event.focused);
+    let hidden =
+        occluded.read().any(|event| event.occluded);
+    // some platforms report minimizing as a resize to
+    // nothing instead
+    let minimized = resized.read().any(|event| {
+        event.width == 0. || event.height == 0.
+    });
+    if !settings.pause_on_focus_loss || time.is_paused() {
+        return;
+    }
+    if focus_lost || hidden || minimized {
+        info!("window lost focus, pausing");
+        time.pause();
+        physics_time.pause();
     }
 }
 
+fn sync_pausable(
+    time: Res<Time<Virtual>>,
+    mut pausable: ResMut<Pausable>,
+) {
+    pausable.set_if_neq(if time.is_paused() {
+        Pausable::Paused
+    } else {
+        Pausable::NotPaused
+    });
+}
+
 /// A [Condition](http://localhost:8000/bevy/ecs/prelude/trait.Condition.html) that enables systems running when the app is paused.
 /// Likely used with [not](http://localhost:8000/bevy/ecs/schedule/common_conditions/fn.not.html)
 ///
@@ -65,59 +209,100 @@ pub fn paused() -> impl Condition<()> {
 }
 
 /// A one-shot system to pause the game
-pub fn pause(mut pausable: ResMut<Pausable>) {
-    *pausable = Pausable::Paused;
+pub fn pause(
+    mut time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    time.pause();
+    physics_time.pause();
 }
 
 /// A one-shot system to unpause the game
-pub fn unpause(mut pausable: ResMut<Pausable>) {
-    *pausable = Pausable::NotPaused;
+pub fn unpause(
+    mut time: ResMut<Time<Virtual>>,
+    mut physics_time: ResMut<Time<Physics>>,
+) {
+    time.unpause();
+    physics_time.unpause();
 }
 
 fn hide_pause_menu(
     mut commands: Commands,
     menu: Res<PauseMenu>,
+    mut menu_stack: ResMut<MenuStack>,
 ) {
     commands.entity(menu.0).despawn_recursive();
+    commands.remove_resource::<PauseMenu>();
+    // settings may have been left open
+    if menu_stack.depth() > 1 {
+        menu_stack.clear();
+    }
 }
-fn show_pause_menu(
-    mut commands: Commands,
-    images: Res<ImageAssets>,
-) {
-    let panel_slicer = TextureSlicer {
-        border: BorderRect::square(20.0),
-        center_scale_mode: SliceScaleMode::Stretch,
-        sides_scale_mode: SliceScaleMode::Stretch,
-        max_corner_scale: 1.0,
-    };
 
-    let pause_text = commands
-        .spawn((
-            ImageBundle {
-                style: Style {
-                    padding: UiRect::all(Val::Px(20.)),
-                    ..default()
-                },
-                image: images.panel_glass.clone().into(),
+fn panel_bundle(
+    images: &ImageAssets,
+) -> (NodeBundle, NineSliceImage) {
+    (
+        NodeBundle {
+            style: Style {
+                width: Val::Px(320.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.),
+                padding: UiRect::all(Val::Px(20.)),
                 ..default()
             },
-            ImageScaleMode::Sliced(panel_slicer.clone()),
-        ))
-        .with_children(|parent| {
-            parent.spawn(
-                TextBundle::from_section(
-                    "Game Paused",
-                    TextStyle {
-                        font_size: 25.0,
-                        color: Color::WHITE,
-                        ..default()
-                    },
-                )
-                .with_text_justify(JustifyText::Center),
-            );
-        })
-        .id();
+            ..default()
+        },
+        NineSliceImage::new(
+            images.ui_sheet.clone(),
+            PANEL_TEXTURE,
+        ),
+    )
+}
+
+fn panel_title(text: &str) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font_size: 25.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    )
+    .with_text_justify(JustifyText::Center)
+}
+
+/// The one-shot systems behind the pause menu's buttons
+#[derive(Resource)]
+struct PauseMenuActions {
+    resume: SystemId,
+    confirm_restart: SystemId,
+    confirm_quit: SystemId,
+    restart: SystemId,
+    cancel: SystemId,
+}
 
+fn show_pause_menu(
+    mut commands: Commands,
+    images: Res<ImageAssets>,
+    actions: Res<PauseMenuActions>,
+) {
+    let buttons = [
+        ("Resume", ButtonAction::Run(actions.resume)),
+        (
+            "Restart",
+            ButtonAction::Run(actions.confirm_restart),
+        ),
+        (
+            "Settings",
+            ButtonAction::PushPage(MenuPage::Settings),
+        ),
+        (
+            "Quit to Menu",
+            ButtonAction::Run(actions.confirm_quit),
+        ),
+    ];
     let pause_menu_id = commands
         .spawn(NodeBundle {
             background_color: Color::rgba(
@@ -127,15 +312,167 @@ fn show_pause_menu(
             style: Style {
                 display: Display::Flex,
                 justify_content: JustifyContent::Center,
-                align_content: AlignContent::Center,
+                align_items: AlignItems::Center,
+                position_type: PositionType::Absolute,
                 width: Val::Percent(100.),
-                align_self: AlignSelf::Center,
+                height: Val::Percent(100.),
                 ..default()
             },
             ..default()
         })
-        .add_child(pause_text)
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    panel_bundle(&images),
+                    PausePanel,
+                    MenuFocus(0),
+                    MenuBack(ButtonAction::Run(
+                        actions.resume,
+                    )),
+                ))
+                .with_children(|parent| {
+                    parent
+                        .spawn(panel_title("Game Paused"));
+                    let entity = parent.parent_entity();
+                    for (text, action) in buttons {
+                        parent.add_command(SpawnButton {
+                            parent: entity,
+                            text,
+                            action,
+                        });
+                    }
+                });
+        })
         .id();
 
     commands.insert_resource(PauseMenu(pause_menu_id));
 }
+
+fn show_confirm_dialog(
+    commands: &mut Commands,
+    images: &ImageAssets,
+    menu: &PauseMenu,
+    actions: &PauseMenuActions,
+    dialog: ConfirmDialog,
+) {
+    let (question, confirm, confirm_action) = match dialog {
+        ConfirmDialog::Restart => (
+            "Restart and lose this run?",
+            "Yes, Restart",
+            ButtonAction::Run(actions.restart),
+        ),
+        ConfirmDialog::QuitToMenu => (
+            "Quit and lose this run?",
+            "Yes, Quit",
+            ButtonAction::ChangeState(GameState::Menu),
+        ),
+    };
+    let (mut node, image) = panel_bundle(images);
+    // on top of the hidden pause panel, rather than
+    // next to it
+    node.style.position_type = PositionType::Absolute;
+    let dialog = commands
+        .spawn((
+            node,
+            image,
+            dialog,
+            // start on the harmless choice
+            MenuFocus(1),
+            MenuBack(ButtonAction::Run(actions.cancel)),
+        ))
+        .with_children(|parent| {
+            parent.spawn(panel_title(question));
+            let entity = parent.parent_entity();
+            parent.add_command(SpawnButton {
+                parent: entity,
+                text: confirm,
+                action: confirm_action,
+            });
+            parent.add_command(SpawnButton {
+                parent: entity,
+                text: "Cancel",
+                action: ButtonAction::Run(actions.cancel),
+            });
+        })
+        .id();
+    commands.entity(menu.0).add_child(dialog);
+}
+
+fn confirm_restart(
+    mut commands: Commands,
+    images: Res<ImageAssets>,
+    menu: Res<PauseMenu>,
+    actions: Res<PauseMenuActions>,
+    mut panel: Query<&mut Visibility, With<PausePanel>>,
+) {
+    for mut visibility in &mut panel {
+        *visibility = Visibility::Hidden;
+    }
+    show_confirm_dialog(
+        &mut commands,
+        &images,
+        &menu,
+        &actions,
+        ConfirmDialog::Restart,
+    );
+}
+
+fn confirm_quit(
+    mut commands: Commands,
+    images: Res<ImageAssets>,
+    menu: Res<PauseMenu>,
+    actions: Res<PauseMenuActions>,
+    mut panel: Query<&mut Visibility, With<PausePanel>>,
+) {
+    for mut visibility in &mut panel {
+        *visibility = Visibility::Hidden;
+    }
+    show_confirm_dialog(
+        &mut commands,
+        &images,
+        &menu,
+        &actions,
+        ConfirmDialog::QuitToMenu,
+    );
+}
+
+fn close_confirm_dialog(
+    mut commands: Commands,
+    dialogs: Query<Entity, With<ConfirmDialog>>,
+    mut panel: Query<&mut Visibility, With<PausePanel>>,
+) {
+    for dialog in &dialogs {
+        commands.entity(dialog).despawn_recursive();
+    }
+    for mut visibility in &mut panel {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn restart_run(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    commands.insert_resource(RestartWithSameShip);
+    next_state.set(GameState::ChooseShip);
+}
+
+/// Pages like settings are shared with the main menu and
+/// drawn in its place, so step out of their way
+fn hide_pause_panel_for_settings(
+    menu_stack: Res<MenuStack>,
+    dialogs: Query<(), With<ConfirmDialog>>,
+    mut panel: Query<&mut Visibility, With<PausePanel>>,
+) {
+    if !menu_stack.is_changed() || !dialogs.is_empty() {
+        return;
+    }
+    for mut visibility in &mut panel {
+        *visibility =
+            if menu_stack.current() != MenuPage::Main {
+                Visibility::Hidden
+            } else {
+                Visibility::Inherited
+            };
+    }
+}
@@ -0,0 +1,517 @@
+//! The settings page of the main menu, also opened from
+//! the pause menu. Settings are grouped into sections,
+//! picked with the tabs along the top of the page.
+
+use bevy::{ecs::system::Command, prelude::*};
+
+use super::{
+    button::{ButtonAction, SpawnButton},
+    focus::{FocusSet, Focusable, MenuBack, MenuFocus},
+    nine_slice::NineSliceImage,
+    widgets::{
+        spawn_widget_button, Binding, SpawnCheckbox,
+        SpawnDropdown, SpawnKeyBinding, SpawnSlider,
+        SpawnStepper, SELECTED_WIDGET_TEXTURE,
+        WIDGET_TEXTURE,
+    },
+    MenuPage, PANEL_TEXTURE,
+};
+use crate::{
+    assets::ImageAssets,
+    settings::{
+        AudioSettings, Difficulty, DisplayMode,
+        StatsExport, RESOLUTIONS,
+    },
+    GameState,
+};
+
+pub struct SettingsPagePlugin;
+
+impl Plugin for SettingsPagePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SettingsSection::Audio)
+            .add_systems(
+                Update,
+                (
+                    pick_settings_section,
+                    show_settings_section,
+                )
+                    .chain()
+                    .after(FocusSet)
+                    .run_if(not(in_state(
+                        GameState::AssetLoading,
+                    ))),
+            );
+    }
+}
+
+#[derive(
+    Resource, Component, Debug, Clone, Copy, PartialEq, Eq,
+)]
+pub enum SettingsSection {
+    Audio,
+    Controls,
+    Display,
+    Gameplay,
+    Accessibility,
+}
+
+impl SettingsSection {
+    /// in the order of the tabs
+    pub const ALL: [SettingsSection; 5] = [
+        SettingsSection::Audio,
+        SettingsSection::Controls,
+        SettingsSection::Display,
+        SettingsSection::Gameplay,
+        SettingsSection::Accessibility,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            SettingsSection::Audio => "Audio",
+            SettingsSection::Controls => "Controls",
+            SettingsSection::Display => "Display",
+            SettingsSection::Gameplay => "Gameplay",
+            SettingsSection::Accessibility => {
+                "Accessibility"
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsTab(SettingsSection);
+
+fn percent(value: f32) -> String {
+    format!("{:.0}%", value * 100.)
+}
+
+/// Spawns the settings page, hidden, under `parent`
+pub struct SpawnSettingsPage {
+    pub parent: Entity,
+}
+
+impl Command for SpawnSettingsPage {
+    fn apply(self, world: &mut World) {
+        let sheet = world
+            .resource::<ImageAssets>()
+            .ui_sheet
+            .clone();
+        let current = *world.resource::<SettingsSection>();
+
+        let page = world
+            .spawn((
+                NodeBundle {
+                    visibility: Visibility::Hidden,
+                    style: Style {
+                        width: Val::Px(600.0),
+                        height: Val::Px(600.0),
+                        flex_direction:
+                            FlexDirection::Column,
+                        row_gap: Val::Px(12.0),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                NineSliceImage::new(sheet, PANEL_TEXTURE),
+                MenuPage::Settings,
+                MenuFocus::default(),
+                MenuBack(ButtonAction::PopPage),
+            ))
+            .set_parent(self.parent)
+            .id();
+
+        let tabs = world
+            .spawn(NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    column_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .set_parent(page)
+            .id();
+        for section in SettingsSection::ALL {
+            let tab = spawn_widget_button(
+                world,
+                tabs,
+                Style {
+                    flex_grow: 1.0,
+                    height: Val::Px(30.0),
+                    ..default()
+                },
+                section.title().into(),
+            );
+            world
+                .entity_mut(tab)
+                .insert((SettingsTab(section), Focusable));
+        }
+
+        for section in SettingsSection::ALL {
+            let node = world
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            display: if section == current {
+                                Display::Flex
+                            } else {
+                                Display::None
+                            },
+                            width: Val::Percent(100.0),
+                            flex_grow: 1.0,
+                            flex_direction:
+                                FlexDirection::Column,
+                            row_gap: Val::Px(12.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    section,
+                ))
+                .set_parent(page)
+                .id();
+            spawn_section(world, section, node);
+        }
+
+        SpawnButton {
+            parent: page,
+            text: "Back",
+            action: ButtonAction::PopPage,
+        }
+        .apply(world);
+    }
+}
+
+fn spawn_section(
+    world: &mut World,
+    section: SettingsSection,
+    parent: Entity,
+) {
+    match section {
+        SettingsSection::Audio => {
+            SpawnCheckbox {
+                parent,
+                label: "Play Audio",
+                binding: Binding {
+                    get: |settings| {
+                        settings.audio == AudioSettings::ON
+                    },
+                    set: |settings, on| {
+                        settings.audio = if on {
+                            AudioSettings::ON
+                        } else {
+                            AudioSettings::OFF
+                        };
+                    },
+                },
+            }
+            .apply(world);
+            SpawnSlider {
+                parent,
+                label: "Master Volume",
+                binding: Binding {
+                    get: |settings| settings.master_volume,
+                    set: |settings, volume| {
+                        settings.master_volume = volume;
+                    },
+                },
+                min: 0.,
+                max: 1.,
+                format: percent,
+            }
+            .apply(world);
+            SpawnSlider {
+                parent,
+                label: "Effects Volume",
+                binding: Binding {
+                    get: |settings| settings.effects_volume,
+                    set: |settings, volume| {
+                        settings.effects_volume = volume;
+                    },
+                },
+                min: 0.,
+                max: 1.,
+                format: percent,
+            }
+            .apply(world);
+        }
+        SettingsSection::Controls => {
+            SpawnKeyBinding {
+                parent,
+                label: "Thrust",
+                binding: Binding {
+                    get: |settings| {
+                        settings.controls.thrust
+                    },
+                    set: |settings, key| {
+                        settings.controls.thrust = key;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnKeyBinding {
+                parent,
+                label: "Rotate Left",
+                binding: Binding {
+                    get: |settings| {
+                        settings.controls.rotate_left
+                    },
+                    set: |settings, key| {
+                        settings.controls.rotate_left = key;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnKeyBinding {
+                parent,
+                label: "Rotate Right",
+                binding: Binding {
+                    get: |settings| {
+                        settings.controls.rotate_right
+                    },
+                    set: |settings, key| {
+                        settings.controls.rotate_right =
+                            key;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnKeyBinding {
+                parent,
+                label: "Fire",
+                binding: Binding {
+                    get: |settings| settings.controls.fire,
+                    set: |settings, key| {
+                        settings.controls.fire = key;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnKeyBinding {
+                parent,
+                label: "Pause",
+                binding: Binding {
+                    get: |settings| settings.controls.pause,
+                    set: |settings, key| {
+                        settings.controls.pause = key;
+                    },
+                },
+            }
+            .apply(world);
+        }
+        SettingsSection::Display => {
+            SpawnDropdown {
+                parent,
+                label: "Window Mode",
+                binding: Binding {
+                    get: |settings| {
+                        settings.display_mode as usize
+                    },
+                    set: |settings, index| {
+                        settings.display_mode =
+                            DisplayMode::ALL[index];
+                    },
+                },
+                options: &[
+                    "Windowed",
+                    "Borderless",
+                    "Fullscreen",
+                ],
+            }
+            .apply(world);
+            SpawnDropdown {
+                parent,
+                label: "Resolution",
+                binding: Binding {
+                    get: |settings| {
+                        RESOLUTIONS
+                            .iter()
+                            .position(|resolution| {
+                                *resolution
+                                    == settings.resolution
+                            })
+                            .unwrap_or_default()
+                    },
+                    set: |settings, index| {
+                        settings.resolution =
+                            RESOLUTIONS[index];
+                    },
+                },
+                options: &[
+                    "1280 x 720",
+                    "1600 x 900",
+                    "1920 x 1080",
+                    "2560 x 1440",
+                ],
+            }
+            .apply(world);
+            SpawnCheckbox {
+                parent,
+                label: "VSync",
+                binding: Binding {
+                    get: |settings| settings.vsync,
+                    set: |settings, on| {
+                        settings.vsync = on;
+                    },
+                },
+            }
+            .apply(world);
+        }
+        SettingsSection::Gameplay => {
+            SpawnDropdown {
+                parent,
+                label: "Difficulty",
+                binding: Binding {
+                    get: |settings| {
+                        settings.difficulty as usize
+                    },
+                    set: |settings, index| {
+                        settings.difficulty =
+                            Difficulty::ALL[index];
+                    },
+                },
+                options: &["Easy", "Normal", "Hard"],
+            }
+            .apply(world);
+            SpawnSlider {
+                parent,
+                label: "Screen Shake",
+                binding: Binding {
+                    get: |settings| settings.screen_shake,
+                    set: |settings, shake| {
+                        settings.screen_shake = shake;
+                    },
+                },
+                min: 0.,
+                max: 1.,
+                format: percent,
+            }
+            .apply(world);
+            SpawnStepper {
+                parent,
+                label: "Particles",
+                binding: Binding {
+                    get: |settings| {
+                        settings.particle_density
+                    },
+                    set: |settings, density| {
+                        settings.particle_density = density;
+                    },
+                },
+                min: 0.,
+                max: 1.,
+                step: 0.25,
+                format: percent,
+            }
+            .apply(world);
+            SpawnCheckbox {
+                parent,
+                label: "Score Popups",
+                binding: Binding {
+                    get: |settings| settings.score_popups,
+                    set: |settings, on| {
+                        settings.score_popups = on;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnCheckbox {
+                parent,
+                label: "Pause When Unfocused",
+                binding: Binding {
+                    get: |settings| {
+                        settings.pause_on_focus_loss
+                    },
+                    set: |settings, on| {
+                        settings.pause_on_focus_loss = on;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnDropdown {
+                parent,
+                label: "Export Runs",
+                binding: Binding {
+                    get: |settings| {
+                        settings.stats_export as usize
+                    },
+                    set: |settings, index| {
+                        settings.stats_export =
+                            StatsExport::ALL[index];
+                    },
+                },
+                options: &["Off", "CSV", "JSON Lines"],
+            }
+            .apply(world);
+        }
+        SettingsSection::Accessibility => {
+            SpawnCheckbox {
+                parent,
+                label: "Reduce Motion",
+                binding: Binding {
+                    get: |settings| settings.reduce_motion,
+                    set: |settings, on| {
+                        settings.reduce_motion = on;
+                    },
+                },
+            }
+            .apply(world);
+            SpawnStepper {
+                parent,
+                label: "UI Scale",
+                binding: Binding {
+                    get: |settings| settings.ui_scale,
+                    set: |settings, scale| {
+                        settings.ui_scale = scale;
+                    },
+                },
+                min: 0.75,
+                max: 1.5,
+                step: 0.25,
+                format: percent,
+            }
+            .apply(world);
+        }
+    }
+}
+
+fn pick_settings_section(
+    tabs: Query<
+        (&Interaction, &SettingsTab),
+        Changed<Interaction>,
+    >,
+    mut current: ResMut<SettingsSection>,
+) {
+    for (interaction, tab) in &tabs {
+        if *interaction == Interaction::Pressed {
+            current.set_if_neq(tab.0);
+        }
+    }
+}
+
+fn show_settings_section(
+    current: Res<SettingsSection>,
+    mut sections: Query<(&SettingsSection, &mut Style)>,
+    mut tabs: Query<(&SettingsTab, &mut NineSliceImage)>,
+) {
+    if !current.is_changed() {
+        return;
+    }
+    for (section, mut style) in &mut sections {
+        style.display = if *section == *current {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    for (tab, mut image) in &mut tabs {
+        image.name = if tab.0 == *current {
+            SELECTED_WIDGET_TEXTURE
+        } else {
+            WIDGET_TEXTURE
+        }
+        .into();
+    }
+}
@@ -0,0 +1,954 @@
+//! Controls bound to a field of [`GameSettings`].
+//!
+//! Like [`SpawnButton`](super::button::SpawnButton), each
+//! widget is spawned by a command, as a labelled row
+//! under `parent`. A [`Binding`] reads and writes the
+//! setting the widget controls. Using the widget updates
+//! the setting, and every widget redraws when the
+//! settings change, wherever the change came from.
+//!
+//! Widgets can be focused from the keyboard or a
+//! gamepad, see [`super::focus`]. Left and right move
+//! sliders, steppers and dropdowns.
+
+use bevy::{
+    ecs::system::Command,
+    prelude::*,
+    ui::{RelativeCursorPosition, UiSystem},
+};
+
+use super::{
+    button::HOVERED_BUTTON,
+    focus::{
+        AdjustWithArrows, FocusAdjust, FocusSet, Focusable,
+    },
+    nine_slice::NineSliceImage,
+};
+use crate::{
+    assets::{FontAssets, ImageAssets},
+    colors,
+    settings::GameSettings,
+    GameState,
+};
+
+pub struct WidgetsPlugin;
+
+impl Plugin for WidgetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (
+                    toggle_checkboxes,
+                    drag_sliders,
+                    step_steppers,
+                    toggle_dropdowns,
+                    choose_dropdown_options,
+                    adjust_focused_widgets,
+                ),
+                (
+                    refresh_checkboxes,
+                    refresh_sliders,
+                    refresh_steppers,
+                    refresh_dropdowns,
+                    refresh_key_bindings,
+                ),
+                highlight_widget_buttons,
+            )
+                .chain()
+                .after(FocusSet)
+                .run_if(not(in_state(
+                    GameState::AssetLoading,
+                ))),
+        )
+        .add_systems(
+            PreUpdate,
+            rebind_keys.after(UiSystem::Focus).run_if(not(
+                in_state(GameState::AssetLoading),
+            )),
+        );
+    }
+}
+
+/// Sliced texture of the widgets' buttons and boxes,
+/// from the ui sheet
+pub(crate) const WIDGET_TEXTURE: &str = "squareWhite.png";
+/// Marks the selected one of a group of widget buttons
+pub(crate) const SELECTED_WIDGET_TEXTURE: &str =
+    "squareBlue.png";
+pub(crate) const SLIDER_TRACK_TEXTURE: &str =
+    "square_shadow.png";
+const SLIDER_FILL_TEXTURE: &str = SELECTED_WIDGET_TEXTURE;
+
+/// Reads and writes one field of [`GameSettings`], built
+/// from closures that capture nothing:
+///
+/// ```ignore
+/// Binding {
+///     get: |settings| settings.score_popups,
+///     set: |settings, on| settings.score_popups = on,
+/// }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Binding<T> {
+    pub get: fn(&GameSettings) -> T,
+    pub set: fn(&mut GameSettings, T),
+}
+
+impl<T: PartialEq> Binding<T> {
+    /// Write `value`, leaving the settings untouched (and
+    /// unchanged) if it is already set
+    fn update(
+        &self,
+        settings: &mut ResMut<GameSettings>,
+        value: T,
+    ) {
+        if (self.get)(settings) != value {
+            (self.set)(settings, value);
+        }
+    }
+}
+
+/// A button or box belonging to a widget, tinted while
+/// hovered
+#[derive(Component)]
+struct WidgetButton;
+
+#[derive(Component)]
+struct Checkbox {
+    binding: Binding<bool>,
+}
+
+/// The track of a slider, pressed or dragged to pick a
+/// value between `min` and `max`
+#[derive(Component)]
+struct Slider {
+    binding: Binding<f32>,
+    min: f32,
+    max: f32,
+    format: fn(f32) -> String,
+    fill: Entity,
+    value_text: Entity,
+}
+
+impl Slider {
+    fn fraction(&self, value: f32) -> f32 {
+        ((value - self.min) / (self.max - self.min))
+            .clamp(0., 1.)
+    }
+}
+
+#[derive(Component)]
+struct Stepper {
+    binding: Binding<f32>,
+    min: f32,
+    max: f32,
+    step: f32,
+    format: fn(f32) -> String,
+    value_text: Entity,
+}
+
+/// One of the `<` `>` buttons of a [`Stepper`]
+#[derive(Component)]
+struct StepperArrow {
+    stepper: Entity,
+    direction: f32,
+}
+
+/// The button showing the selected option, pressing it
+/// opens and closes the `list` of options
+#[derive(Component)]
+struct Dropdown {
+    binding: Binding<usize>,
+    options: &'static [&'static str],
+    selected_text: Entity,
+    list: Entity,
+}
+
+#[derive(Component)]
+struct DropdownOption {
+    dropdown: Entity,
+    index: usize,
+}
+
+/// Shows a bound key. Pressing it waits for the next
+/// key press and binds that key instead.
+#[derive(Component)]
+struct KeyBindingButton {
+    binding: Binding<KeyCode>,
+    text: Entity,
+    listening: bool,
+}
+
+/// How a key is shown on a [`KeyBindingButton`]
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+fn text_style(world: &World, font_size: f32) -> TextStyle {
+    TextStyle {
+        font: world.resource::<FontAssets>().roboto.clone(),
+        font_size,
+        color: colors::TEXT,
+    }
+}
+
+/// Spawn a row under `parent` with `label` on the left,
+/// returning the row for the control to be added to
+fn spawn_row(
+    world: &mut World,
+    parent: Entity,
+    label: String,
+) -> Entity {
+    let style = text_style(world, 25.0);
+    world
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                justify_content:
+                    JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        })
+        .set_parent(parent)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label, style,
+            ));
+        })
+        .id()
+}
+
+/// A small sliced button holding `text`
+pub(crate) fn spawn_widget_button(
+    world: &mut World,
+    parent: Entity,
+    style: Style,
+    text: String,
+) -> Entity {
+    let sheet =
+        world.resource::<ImageAssets>().ui_sheet.clone();
+    let text_style = text_style(world, 20.0);
+    world
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..style
+                },
+                background_color: Color::NONE.into(),
+                ..default()
+            },
+            NineSliceImage::new(sheet, WIDGET_TEXTURE),
+            WidgetButton,
+        ))
+        .set_parent(parent)
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text, text_style,
+            ));
+        })
+        .id()
+}
+
+/// The text child of a [`spawn_widget_button`] button
+fn button_text(world: &World, button: Entity) -> Entity {
+    world
+        .get::<Children>(button)
+        .and_then(|children| {
+            children.iter().copied().find(|child| {
+                world.get::<Text>(*child).is_some()
+            })
+        })
+        .expect("widget buttons to have a text child")
+}
+
+pub struct SpawnCheckbox<T: Into<String>> {
+    pub parent: Entity,
+    pub label: T,
+    pub binding: Binding<bool>,
+}
+
+impl<T: Into<String> + Send + 'static> Command
+    for SpawnCheckbox<T>
+{
+    fn apply(self, world: &mut World) {
+        let checked = (self.binding.get)(
+            world.resource::<GameSettings>(),
+        );
+        let image = checkbox_image(
+            world.resource::<ImageAssets>(),
+            checked,
+        );
+        let row = spawn_row(
+            world,
+            self.parent,
+            self.label.into(),
+        );
+        world
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(25.0),
+                        height: Val::Px(25.0),
+                        ..default()
+                    },
+                    image: UiImage::new(image),
+                    ..default()
+                },
+                Checkbox {
+                    binding: self.binding,
+                },
+                Focusable,
+            ))
+            .set_parent(row);
+    }
+}
+
+fn checkbox_image(
+    images: &ImageAssets,
+    checked: bool,
+) -> Handle<Image> {
+    if checked {
+        images.box_checked.clone()
+    } else {
+        images.box_unchecked.clone()
+    }
+}
+
+pub struct SpawnSlider<T: Into<String>> {
+    pub parent: Entity,
+    pub label: T,
+    pub binding: Binding<f32>,
+    pub min: f32,
+    pub max: f32,
+    /// how the value is shown next to the slider
+    pub format: fn(f32) -> String,
+}
+
+impl<T: Into<String> + Send + 'static> Command
+    for SpawnSlider<T>
+{
+    fn apply(self, world: &mut World) {
+        let value = (self.binding.get)(
+            world.resource::<GameSettings>(),
+        );
+        let sheet = world
+            .resource::<ImageAssets>()
+            .ui_sheet
+            .clone();
+        let style = text_style(world, 20.0);
+        let row = spawn_row(
+            world,
+            self.parent,
+            self.label.into(),
+        );
+
+        let track = world
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(140.0),
+                        height: Val::Px(20.0),
+                        margin: UiRect::left(Val::Auto),
+                        ..default()
+                    },
+                    background_color: Color::NONE.into(),
+                    ..default()
+                },
+                NineSliceImage::new(
+                    sheet.clone(),
+                    SLIDER_TRACK_TEXTURE,
+                ),
+                RelativeCursorPosition::default(),
+                WidgetButton,
+                Focusable,
+                AdjustWithArrows,
+            ))
+            .set_parent(row)
+            .id();
+        let fill = world
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        height: Val::Percent(100.0),
+                        // the slices' corners
+                        min_width: Val::Px(12.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                NineSliceImage::new(
+                    sheet,
+                    SLIDER_FILL_TEXTURE,
+                ),
+            ))
+            .set_parent(track)
+            .id();
+        let value_text = world
+            .spawn(
+                TextBundle::from_section(
+                    (self.format)(value),
+                    style,
+                )
+                .with_style(Style {
+                    min_width: Val::Px(50.0),
+                    ..default()
+                }),
+            )
+            .set_parent(row)
+            .id();
+
+        let slider = Slider {
+            binding: self.binding,
+            min: self.min,
+            max: self.max,
+            format: self.format,
+            fill,
+            value_text,
+        };
+        if let Some(mut style) =
+            world.get_mut::<Style>(fill)
+        {
+            style.width =
+                Val::Percent(slider.fraction(value) * 100.);
+        }
+        world.entity_mut(track).insert(slider);
+    }
+}
+
+pub struct SpawnStepper<T: Into<String>> {
+    pub parent: Entity,
+    pub label: T,
+    pub binding: Binding<f32>,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    /// how the value is shown between the arrows
+    pub format: fn(f32) -> String,
+}
+
+impl<T: Into<String> + Send + 'static> Command
+    for SpawnStepper<T>
+{
+    fn apply(self, world: &mut World) {
+        let value = (self.binding.get)(
+            world.resource::<GameSettings>(),
+        );
+        let style = text_style(world, 20.0);
+        let row = spawn_row(
+            world,
+            self.parent,
+            self.label.into(),
+        );
+        let arrow_style = Style {
+            width: Val::Px(30.0),
+            height: Val::Px(30.0),
+            ..default()
+        };
+
+        let controls = world
+            .spawn(NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            })
+            .set_parent(row)
+            .id();
+        let previous = spawn_widget_button(
+            world,
+            controls,
+            arrow_style.clone(),
+            "<".into(),
+        );
+        let value_text = world
+            .spawn(
+                TextBundle::from_section(
+                    (self.format)(value),
+                    style,
+                )
+                .with_text_justify(JustifyText::Center)
+                .with_style(Style {
+                    min_width: Val::Px(60.0),
+                    ..default()
+                }),
+            )
+            .set_parent(controls)
+            .id();
+        let next = spawn_widget_button(
+            world,
+            controls,
+            arrow_style,
+            ">".into(),
+        );
+
+        world.entity_mut(controls).insert((
+            Stepper {
+                binding: self.binding,
+                min: self.min,
+                max: self.max,
+                step: self.step,
+                format: self.format,
+                value_text,
+            },
+            Focusable,
+            AdjustWithArrows,
+        ));
+        world.entity_mut(previous).insert(StepperArrow {
+            stepper: controls,
+            direction: -1.,
+        });
+        world.entity_mut(next).insert(StepperArrow {
+            stepper: controls,
+            direction: 1.,
+        });
+    }
+}
+
+pub struct SpawnDropdown<T: Into<String>> {
+    pub parent: Entity,
+    pub label: T,
+    /// the index of the selected option
+    pub binding: Binding<usize>,
+    pub options: &'static [&'static str],
+}
+
+impl<T: Into<String> + Send + 'static> Command
+    for SpawnDropdown<T>
+{
+    fn apply(self, world: &mut World) {
+        let selected = (self.binding.get)(
+            world.resource::<GameSettings>(),
+        );
+        let row = spawn_row(
+            world,
+            self.parent,
+            self.label.into(),
+        );
+        let option_style = Style {
+            width: Val::Percent(100.0),
+            height: Val::Px(30.0),
+            ..default()
+        };
+
+        let dropdown = spawn_widget_button(
+            world,
+            row,
+            Style {
+                width: Val::Px(150.0),
+                height: Val::Px(30.0),
+                ..default()
+            },
+            self.options
+                .get(selected)
+                .copied()
+                .unwrap_or_default()
+                .into(),
+        );
+        let list = world
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(100.0),
+                    left: Val::Px(0.0),
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                // above the rest of the menu
+                z_index: ZIndex::Global(10),
+                visibility: Visibility::Hidden,
+                ..default()
+            })
+            .set_parent(dropdown)
+            .id();
+        for (index, option) in
+            self.options.iter().enumerate()
+        {
+            let option = spawn_widget_button(
+                world,
+                list,
+                option_style.clone(),
+                (*option).into(),
+            );
+            world.entity_mut(option).insert((
+                DropdownOption { dropdown, index },
+                Focusable,
+            ));
+        }
+
+        let selected_text = button_text(world, dropdown);
+        world.entity_mut(dropdown).insert((
+            Dropdown {
+                binding: self.binding,
+                options: self.options,
+                selected_text,
+                list,
+            },
+            Focusable,
+            AdjustWithArrows,
+        ));
+    }
+}
+
+pub struct SpawnKeyBinding<T: Into<String>> {
+    pub parent: Entity,
+    pub label: T,
+    pub binding: Binding<KeyCode>,
+}
+
+impl<T: Into<String> + Send + 'static> Command
+    for SpawnKeyBinding<T>
+{
+    fn apply(self, world: &mut World) {
+        let key = (self.binding.get)(
+            world.resource::<GameSettings>(),
+        );
+        let row = spawn_row(
+            world,
+            self.parent,
+            self.label.into(),
+        );
+        let button = spawn_widget_button(
+            world,
+            row,
+            Style {
+                width: Val::Px(150.0),
+                height: Val::Px(30.0),
+                ..default()
+            },
+            key_name(key),
+        );
+        let text = button_text(world, button);
+        world.entity_mut(button).insert((
+            KeyBindingButton {
+                binding: self.binding,
+                text,
+                listening: false,
+            },
+            Focusable,
+        ));
+    }
+}
+
+fn toggle_checkboxes(
+    checkboxes: Query<
+        (&Interaction, &Checkbox),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, checkbox) in &checkboxes {
+        if *interaction == Interaction::Pressed {
+            let checked = (checkbox.binding.get)(&settings);
+            checkbox
+                .binding
+                .update(&mut settings, !checked);
+        }
+    }
+}
+
+/// Sliders follow the cursor for as long as they are
+/// held with the mouse
+fn drag_sliders(
+    sliders: Query<(
+        &Interaction,
+        &RelativeCursorPosition,
+        &Slider,
+    )>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    // pressing Enter on a focused slider doesn't move it
+    if !mouse_input.pressed(MouseButton::Left) {
+        return;
+    }
+    for (interaction, cursor, slider) in &sliders {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let value = slider.min
+            + position.x.clamp(0., 1.)
+                * (slider.max - slider.min);
+        slider.binding.update(&mut settings, value);
+    }
+}
+
+fn step_steppers(
+    arrows: Query<
+        (&Interaction, &StepperArrow),
+        Changed<Interaction>,
+    >,
+    steppers: Query<&Stepper>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, arrow) in &arrows {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok(stepper) = steppers.get(arrow.stepper)
+        else {
+            warn!("stepper arrow without a stepper");
+            continue;
+        };
+        let value = ((stepper.binding.get)(&settings)
+            + arrow.direction * stepper.step)
+            .clamp(stepper.min, stepper.max);
+        stepper.binding.update(&mut settings, value);
+    }
+}
+
+fn toggle_dropdowns(
+    dropdowns: Query<
+        (&Interaction, &Dropdown),
+        Changed<Interaction>,
+    >,
+    mut lists: Query<&mut Visibility>,
+) {
+    for (interaction, dropdown) in &dropdowns {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok(mut visibility) =
+            lists.get_mut(dropdown.list)
+        else {
+            continue;
+        };
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn choose_dropdown_options(
+    options: Query<
+        (&Interaction, &DropdownOption),
+        Changed<Interaction>,
+    >,
+    dropdowns: Query<&Dropdown>,
+    mut lists: Query<&mut Visibility>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, option) in &options {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok(dropdown) = dropdowns.get(option.dropdown)
+        else {
+            warn!("dropdown option without a dropdown");
+            continue;
+        };
+        dropdown
+            .binding
+            .update(&mut settings, option.index);
+        if let Ok(mut visibility) =
+            lists.get_mut(dropdown.list)
+        {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Runs as soon as input and interactions are known,
+/// so the key that is bound can be consumed before
+/// anything else acts on it
+fn rebind_keys(
+    mut buttons: Query<(
+        Ref<Interaction>,
+        &mut KeyBindingButton,
+    )>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut texts: Query<&mut Text>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, mut button) in &mut buttons {
+        let Ok(mut text) = texts.get_mut(button.text)
+        else {
+            continue;
+        };
+        if !button.listening {
+            if interaction.is_changed()
+                && *interaction == Interaction::Pressed
+            {
+                button.listening = true;
+                text.sections[0].value =
+                    "Press a key".into();
+            }
+            continue;
+        }
+        let Some(key) =
+            keys.get_just_pressed().next().copied()
+        else {
+            continue;
+        };
+        keys.reset(key);
+        button.listening = false;
+        text.sections[0].value = key_name(key);
+        button.binding.update(&mut settings, key);
+    }
+}
+
+/// Left and right on a focused widget. Sliders move a
+/// tenth of their range, dropdowns pick the previous or
+/// next option.
+fn adjust_focused_widgets(
+    mut adjustments: EventReader<FocusAdjust>,
+    sliders: Query<&Slider>,
+    steppers: Query<&Stepper>,
+    dropdowns: Query<&Dropdown>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for FocusAdjust { entity, direction } in
+        adjustments.read()
+    {
+        if let Ok(slider) = sliders.get(*entity) {
+            let value = ((slider.binding.get)(&settings)
+                + direction * (slider.max - slider.min)
+                    / 10.)
+                .clamp(slider.min, slider.max);
+            slider.binding.update(&mut settings, value);
+        } else if let Ok(stepper) = steppers.get(*entity) {
+            let value = ((stepper.binding.get)(&settings)
+                + direction * stepper.step)
+                .clamp(stepper.min, stepper.max);
+            stepper.binding.update(&mut settings, value);
+        } else if let Ok(dropdown) = dropdowns.get(*entity)
+        {
+            let selected =
+                (dropdown.binding.get)(&settings);
+            let index = if *direction < 0. {
+                selected.saturating_sub(1)
+            } else {
+                (selected + 1)
+                    .min(dropdown.options.len() - 1)
+            };
+            dropdown.binding.update(&mut settings, index);
+        }
+    }
+}
+
+fn refresh_checkboxes(
+    settings: Res<GameSettings>,
+    images: Res<ImageAssets>,
+    mut checkboxes: Query<(&Checkbox, &mut UiImage)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (checkbox, mut image) in &mut checkboxes {
+        *image = UiImage::new(checkbox_image(
+            &images,
+            (checkbox.binding.get)(&settings),
+        ));
+    }
+}
+
+fn refresh_sliders(
+    settings: Res<GameSettings>,
+    sliders: Query<&Slider>,
+    mut styles: Query<&mut Style>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for slider in &sliders {
+        let value = (slider.binding.get)(&settings);
+        if let Ok(mut style) = styles.get_mut(slider.fill) {
+            style.width =
+                Val::Percent(slider.fraction(value) * 100.);
+        }
+        if let Ok(mut text) =
+            texts.get_mut(slider.value_text)
+        {
+            text.sections[0].value = (slider.format)(value);
+        }
+    }
+}
+
+fn refresh_steppers(
+    settings: Res<GameSettings>,
+    steppers: Query<&Stepper>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for stepper in &steppers {
+        if let Ok(mut text) =
+            texts.get_mut(stepper.value_text)
+        {
+            text.sections[0].value = (stepper.format)(
+                (stepper.binding.get)(&settings),
+            );
+        }
+    }
+}
+
+fn refresh_dropdowns(
+    settings: Res<GameSettings>,
+    dropdowns: Query<&Dropdown>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for dropdown in &dropdowns {
+        let selected = (dropdown.binding.get)(&settings);
+        let (Some(option), Ok(mut text)) = (
+            dropdown.options.get(selected),
+            texts.get_mut(dropdown.selected_text),
+        ) else {
+            continue;
+        };
+        text.sections[0].value = (*option).into();
+    }
+}
+
+fn refresh_key_bindings(
+    settings: Res<GameSettings>,
+    buttons: Query<&KeyBindingButton>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for button in &buttons {
+        if button.listening {
+            continue;
+        }
+        if let Ok(mut text) = texts.get_mut(button.text) {
+            text.sections[0].value =
+                key_name((button.binding.get)(&settings));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn highlight_widget_buttons(
+    mut buttons: Query<
+        (&Interaction, &mut NineSliceImage),
+        (Changed<Interaction>, With<WidgetButton>),
+    >,
+) {
+    for (interaction, mut image) in &mut buttons {
+        image.color = match interaction {
+            Interaction::None => Color::WHITE,
+            _ => HOVERED_BUTTON,
+        };
+    }
+}